@@ -1,37 +1,80 @@
-use std::{env, str::FromStr, sync::Arc, time::UNIX_EPOCH};
+use std::{
+    collections::BTreeMap,
+    env,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
 
-use apitype::genshin::{GenerateQuery, ProfileQuery, User, UserCharacter};
+use apitype::genshin::{
+    ArtifactRolls, ArtifactSet, Build, BuildGenerateQuery, BuildsQuery, GenerateQuery, Hoyo,
+    Profile, ProfileQuery, RawGenerateQuery, RollsQuery, SubstatRolls, User, UserCharacter,
+};
 use apitype::hsr::{
-    GenerateQuery as HsrGenerateQuery, ProfileQuery as HsrProfileQuery, User as HsrUser,
-    UserCharacter as HsrUserCharacter,
+    GenerateQuery as HsrGenerateQuery, ProfileQuery as HsrProfileQuery, RelicRolls,
+    RelicSet as HsrRelicSet, RollsQuery as HsrRollsQuery, SubstatRolls as HsrSubstatRolls,
+    User as HsrUser, UserCharacter as HsrUserCharacter,
+};
+use apitype::zzz::{
+    GenerateQuery as ZzzGenerateQuery, ProfileQuery as ZzzProfileQuery, User as ZzzUser,
+    UserCharacter as ZzzUserCharacter,
 };
 use axum::{
-    extract::{Query, State},
-    http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing, Json, Router,
 };
 use base64::{engine::general_purpose, Engine as _};
 use env_logger::Builder;
 use gen::{
-    enka_api::{api::Api, character::CharacterId, icon::IconData, DynamicImage},
-    gen::{convert, generate as gen, get_default, ImageFormat, Lang, ScoreCounter},
+    default::{invalid_defaults, missing_defaults},
+    dupe::roll_breakdown,
+    enka_api::{
+        api::Api,
+        cache::EvictionPolicy,
+        character::{Character, CharacterId},
+        error::EnkaError,
+        icon::IconData,
+        DynamicImage,
+    },
+    gen::{
+        convert, generate as gen, get_default, ArtifactMetrics, CardOptions, ImageFormat, Lang,
+        ScoreCounter, TotalMetric,
+    },
+    layout::Layout,
+    style::CardStyle,
 };
+use gen_utils::grades::{GradeSet, GRADES};
 use hsr_gen::base::random_base_image;
 use hsr_gen::{
     base::{get_base_image, BaseImage},
+    default::{
+        get_default as get_hsr_default, invalid_defaults as invalid_hsr_defaults,
+        missing_defaults as missing_hsr_defaults,
+    },
     format::ImageFormat as HsrImageFormat,
-    gen::{generate as hsr_gen, ScoreCounter as HsrScoreCounter},
-    mihomo_api::api::Api as MihoyoApi,
+    gen::{generate as hsr_gen, CardOptions as HsrCardOptions, ScoreCounter as HsrScoreCounter},
+    mihomo_api::{api::Api as MihoyoApi, error::MihomoError},
+    rolls::RelicRolls as HsrRelicRolls,
+    style::CardStyle as HsrCardStyle,
 };
 use log::LevelFilter;
 use tokio::time::Instant;
 use tower_http::cors::{AllowOrigin, CorsLayer};
+use zzz_gen::{
+    format::ImageFormat as ZzzImageFormat,
+    gen::{generate as zzz_gen, ScoreCounter as ZzzScoreCounter},
+    zzz_api::{api::Api as ZzzApi, character::Agent, error::ZzzError},
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub api: Arc<Api>,
     pub icons: Arc<IconData>,
+    /// Card layouts loaded from `LAYOUT_DIR`, by file stem.
+    pub layouts: &'static BTreeMap<String, Layout>,
 }
 
 impl Default for AppState {
@@ -42,10 +85,42 @@ impl Default for AppState {
 
 impl AppState {
     pub fn new() -> Self {
-        let api = Api::new();
+        let mut api = Api::builder();
+        if let Ok(url) = env::var("ENKA_URL") {
+            api = api.enka_url(url);
+        }
+        if let Ok(dir) = env::var("STORE_DIR") {
+            api = api.store_dir(dir);
+        }
+        if let Ok(url) = env::var("ENKA_STORE_URL") {
+            api = api.store_url(url);
+        }
+        let api = api.build();
+        let layouts = match env::var("LAYOUT_DIR").map(Layout::load_dir) {
+            Ok(Ok(layouts)) => layouts
+                .into_iter()
+                .filter_map(|(name, layout)| match layout {
+                    Ok(layout) => Some((name, layout)),
+                    Err(e) => {
+                        log::error!("Skipping layout {}: {}", name, e);
+                        None
+                    }
+                })
+                .collect(),
+            Ok(Err(e)) => {
+                log::error!(
+                    "Failed to read LAYOUT_DIR, using the built-in layout only: {}",
+                    e
+                );
+                BTreeMap::new()
+            }
+            Err(_) => BTreeMap::new(),
+        };
         Self {
             icons: Arc::new(api.icon_data()),
             api: Arc::new(api),
+            // lives as long as the server
+            layouts: Box::leak(Box::new(layouts)),
         }
     }
 }
@@ -63,8 +138,42 @@ impl Default for HsrAppState {
 
 impl HsrAppState {
     pub fn new() -> Self {
-        let api = MihoyoApi::new();
-        Self { api: Arc::new(api) }
+        let mut api = MihoyoApi::builder();
+        if let Ok(url) = env::var("MIHOMO_URL") {
+            api = api.mihomo_url(url);
+        }
+        if let Ok(url) = env::var("STAR_RAIL_RES_URL") {
+            api = api.star_rail_res_url(url);
+        }
+        Self {
+            api: Arc::new(api.build()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ZzzAppState {
+    pub api: Arc<ZzzApi>,
+}
+
+impl Default for ZzzAppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZzzAppState {
+    pub fn new() -> Self {
+        let mut api = ZzzApi::builder();
+        if let Ok(url) = env::var("ENKA_URL") {
+            api = api.enka_url(url);
+        }
+        if let Ok(url) = env::var("ZZZ_STORE_URL") {
+            api = api.store_url(url);
+        }
+        Self {
+            api: Arc::new(api.build()),
+        }
     }
 }
 
@@ -80,16 +189,37 @@ async fn main() {
         .filter_module("api", level)
         .filter_module("enka_api", level)
         .filter_module("mihoyo_api", level)
+        .filter_module("zzz_api", level)
         .init();
+    let state = AppState::new();
+    let hsr_state = HsrAppState::new();
+    let zzz_state = ZzzAppState::new();
+    check_score_profiles(&state, &hsr_state);
+    spawn_cache_sweeper(state.clone(), hsr_state.clone(), zzz_state.clone());
     let hsr = Router::new()
         .route("/profile", routing::get(hsr_profile))
         .route("/generate", routing::get(hsr_generate))
-        .with_state(HsrAppState::new());
+        .route("/rolls", routing::get(hsr_rolls))
+        .with_state(hsr_state.clone());
+    let zzz = Router::new()
+        .route("/profile", routing::get(zzz_profile))
+        .route("/generate", routing::get(zzz_generate))
+        .with_state(zzz_state.clone());
     let app = Router::new()
         .route("/profile", routing::get(profile))
-        .route("/generate", routing::get(generate))
+        .route("/generate", routing::get(generate).post(generate_raw))
+        .route("/rolls", routing::get(rolls))
+        .route("/builds/:username", routing::get(builds_profile))
+        .route("/builds/:username/:hash", routing::get(builds))
+        .route(
+            "/builds/:username/:hash/:id/generate",
+            routing::get(build_generate),
+        )
+        .route("/admin/store/update", routing::post(store_update))
+        .route("/admin/store/reload", routing::post(store_reload))
         .nest("/hsr", hsr)
-        .with_state(AppState::new());
+        .nest("/zzz", zzz)
+        .with_state(state.clone());
     let app = Router::new()
         .nest("/v1", app.clone())
         .nest("/", app)
@@ -101,6 +231,190 @@ async fn main() {
         .unwrap();
 }
 
+/// Logs characters that will be scored with the fallback counter.
+fn check_score_profiles(state: &AppState, hsr_state: &HsrAppState) {
+    for (id, name) in missing_defaults(&state.api.get_store()) {
+        log::warn!("no score profile for {} {}", id, name);
+    }
+    for (id, e) in invalid_defaults().into_iter().chain(invalid_hsr_defaults()) {
+        log::warn!("invalid score profile for {}: {}", id, e);
+    }
+    // The HSR character list is fetched, so it is checked without blocking startup.
+    let api = hsr_state.api.clone();
+    tokio::spawn(async move {
+        match api.character_names().await {
+            Ok(names) => {
+                for (id, name) in missing_hsr_defaults(&names) {
+                    log::warn!("no hsr score profile for {} {}", id, name);
+                }
+            }
+            Err(e) => log::warn!("failed to check hsr score profiles: {}", e),
+        }
+    });
+}
+
+fn env_secs(key: &str) -> Option<Duration> {
+    env::var(key).ok()?.parse().ok().map(Duration::from_secs)
+}
+
+fn spawn_cache_sweeper(state: AppState, hsr_state: HsrAppState, zzz_state: ZzzAppState) {
+    let mut policy = EvictionPolicy {
+        max_bytes: Some(
+            env::var("CACHE_MAX_BYTES")
+                .ok()
+                .and_then(|b| b.parse().ok())
+                .unwrap_or(1024 * 1024 * 1024),
+        ),
+        default_max_age: env_secs("CACHE_MAX_AGE"),
+        ..Default::default()
+    };
+    policy.max_age.insert(
+        "user".to_string(),
+        env_secs("CACHE_MAX_AGE_USER").unwrap_or(Duration::from_secs(60 * 60 * 24 * 7)),
+    );
+    if let Some(age) = env_secs("CACHE_MAX_AGE_UI") {
+        policy.max_age.insert("ui".to_string(), age);
+    }
+    let interval = env_secs("CACHE_SWEEP_INTERVAL").unwrap_or(Duration::from_secs(60 * 60));
+    tokio::spawn(async move {
+        let mut timer = tokio::time::interval(interval);
+        loop {
+            timer.tick().await;
+            let results = [
+                ("enka", state.api.sweep_cache(&policy).await),
+                ("hsr", hsr_state.api.sweep_cache(&policy).await),
+                ("zzz", zzz_state.api.sweep_cache(&policy).await),
+            ];
+            for (name, res) in results {
+                match res {
+                    Ok(removed) => log::info!(
+                        "Swept {} cache: removed {} entries ({} bytes)",
+                        name,
+                        removed.entries,
+                        removed.bytes
+                    ),
+                    Err(e) => log::warn!("Failed to sweep {} cache: {}", name, e),
+                }
+            }
+            let stats = [
+                ("enka", state.api.cache_stats().await),
+                ("hsr", hsr_state.api.cache_stats().await),
+                ("zzz", zzz_state.api.cache_stats().await),
+            ];
+            for (name, res) in stats {
+                match res {
+                    Ok(stats) => log::info!("{} cache stats {:?}", name, stats),
+                    Err(e) => log::warn!("Failed to read {} cache stats: {}", name, e),
+                }
+            }
+        }
+    });
+}
+
+fn enka_error(e: EnkaError) -> Response {
+    log::warn!("Enka request failed: {}", e);
+    let status = match &e {
+        EnkaError::InvalidUid | EnkaError::InvalidUsername => StatusCode::BAD_REQUEST,
+        EnkaError::NotFound | EnkaError::HoyoNotFound => StatusCode::NOT_FOUND,
+        EnkaError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        EnkaError::Maintenance => StatusCode::FAILED_DEPENDENCY,
+        EnkaError::UpstreamHttp(_)
+        | EnkaError::Transport(_)
+        | EnkaError::AssetMissing(_)
+        | EnkaError::Decode(_) => StatusCode::BAD_GATEWAY,
+        EnkaError::Cache(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let mut headers = HeaderMap::new();
+    if let EnkaError::RateLimited {
+        retry_after: Some(s),
+    } = e
+    {
+        headers.insert("Retry-After", s.to_string().parse().unwrap());
+    }
+    (status, headers, e.to_string()).into_response()
+}
+
+fn mihomo_error(e: MihomoError) -> Response {
+    log::warn!("Mihomo request failed: {}", e);
+    let status = match &e {
+        MihomoError::InvalidUid => StatusCode::BAD_REQUEST,
+        MihomoError::NotFound => StatusCode::NOT_FOUND,
+        MihomoError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        MihomoError::Maintenance => StatusCode::FAILED_DEPENDENCY,
+        MihomoError::UpstreamHttp(_) | MihomoError::Transport(_) | MihomoError::Decode(_) => {
+            StatusCode::BAD_GATEWAY
+        }
+    };
+    let mut headers = HeaderMap::new();
+    if let MihomoError::RateLimited {
+        retry_after: Some(s),
+    } = e
+    {
+        headers.insert("Retry-After", s.to_string().parse().unwrap());
+    }
+    (status, headers, e.to_string()).into_response()
+}
+
+fn zzz_error(e: ZzzError) -> Response {
+    log::warn!("Enka.Network ZZZ request failed: {}", e);
+    let status = match &e {
+        ZzzError::InvalidUid => StatusCode::BAD_REQUEST,
+        ZzzError::NotFound => StatusCode::NOT_FOUND,
+        ZzzError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        ZzzError::Maintenance => StatusCode::FAILED_DEPENDENCY,
+        ZzzError::UpstreamHttp(_) | ZzzError::Transport(_) | ZzzError::Decode(_) => {
+            StatusCode::BAD_GATEWAY
+        }
+    };
+    let mut headers = HeaderMap::new();
+    if let ZzzError::RateLimited {
+        retry_after: Some(s),
+    } = e
+    {
+        headers.insert("Retry-After", s.to_string().parse().unwrap());
+    }
+    (status, headers, e.to_string()).into_response()
+}
+
+/// Admin routes are disabled unless `ADMIN_TOKEN` is set and sent as a bearer token.
+fn is_admin(headers: &HeaderMap) -> bool {
+    let Ok(token) = env::var("ADMIN_TOKEN") else {
+        return false;
+    };
+    !token.is_empty()
+        && headers
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            == Some(token.as_str())
+}
+
+async fn store_update(headers: HeaderMap, State(s): State<AppState>) -> impl IntoResponse {
+    if !is_admin(&headers) {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    match s.api.update_store().await {
+        Ok(()) => {
+            log::info!("Store updated");
+            (StatusCode::OK, "Store updated").into_response()
+        }
+        Err(e) => enka_error(e),
+    }
+}
+
+async fn store_reload(headers: HeaderMap, State(s): State<AppState>) -> impl IntoResponse {
+    if !is_admin(&headers) {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    match s.api.reload_store() {
+        Ok(()) => {
+            log::info!("Store reloaded");
+            (StatusCode::OK, "Store reloaded").into_response()
+        }
+        Err(e) => enka_error(e),
+    }
+}
+
 fn trim_image(img: Option<DynamicImage>, format: &str) -> Option<String> {
     img.as_ref()?;
     let img = match ImageFormat::from_str(format) {
@@ -115,7 +429,7 @@ async fn profile(Query(q): Query<ProfileQuery>, State(s): State<AppState>) -> im
     let api = s.api;
     let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
     let format = q.image_format.unwrap_or("png".to_string());
-    if !api.store.locale_list().contains(&&lang) {
+    if !api.get_store().locale_list().contains(&&lang) {
         return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
     }
     match api.simple(q.uid).await {
@@ -123,35 +437,10 @@ async fn profile(Query(q): Query<ProfileQuery>, State(s): State<AppState>) -> im
             let profile = usr.profile();
             let mut characters = Vec::with_capacity(usr.characters.len());
             for c in usr.characters.values() {
-                let icon = trim_image(c.image_icon(&api).await, &format);
-                characters.push(UserCharacter {
-                    ascension: c.ascension_level(),
-                    level: c.level,
-                    element_name: c.element.fight_prop_name().to_string(),
-                    element: c.element.fight_prop_name().to_string(),
-                    xp: c.xp,
-                    name: match c.name(&api, lang.clone()) {
-                        Ok(n) => n.to_string(),
-                        Err(_) => {
-                            return (
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                "Could not get character name correctly",
-                            )
-                                .into_response()
-                        }
-                    },
-                    icon: match icon {
-                        Some(i) => i,
-                        None => {
-                            return (
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                "Could not get character icon correctly",
-                            )
-                                .into_response()
-                        }
-                    },
-                    id: c.id.0,
-                });
+                match user_character(&api, c, &lang, &format).await {
+                    Ok(c) => characters.push(c),
+                    Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+                }
             }
             let usr = User {
                 world_level: profile.world_level(),
@@ -169,51 +458,223 @@ async fn profile(Query(q): Query<ProfileQuery>, State(s): State<AppState>) -> im
             };
             (StatusCode::OK, Json(usr)).into_response()
         }
-        Err(_) => (
-            StatusCode::FAILED_DEPENDENCY,
-            "Enkanetwork has invalid response",
-        )
-            .into_response(),
+        Err(e) => enka_error(e),
     }
 }
 async fn generate(Query(q): Query<GenerateQuery>, State(s): State<AppState>) -> impl IntoResponse {
     log::info!("Generate request {:?}", q);
-    let now = Instant::now();
-    let api = s.api;
+    let api = s.api.clone();
     let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
     let format = match ImageFormat::from_str(&q.image_format.unwrap_or("png".to_string())) {
         Ok(f) => f,
         Err(_) => return (StatusCode::BAD_REQUEST, "Invalid image format").into_response(),
     };
-    let counter = if let Some(c) = q.counter {
-        ScoreCounter::from_str(&c).unwrap_or_else(|_| get_default(&q.cid))
-    } else {
-        get_default(&q.cid)
+    let counter = match score_counter(q.counter, q.cid) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
     };
-    if !api.store.locale_list().contains(&&lang) {
+    if !api.get_store().locale_list().contains(&&lang) {
         return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
     }
     let (usr, from_cache) = match api.simple(q.uid).await {
         Ok((usr, from_cache)) => (usr, from_cache),
-        Err(_) => {
-            return (
-                StatusCode::FAILED_DEPENDENCY,
-                "Enkanetwork had invalid response",
-            )
-                .into_response()
-        }
+        Err(e) => return enka_error(e),
     };
     let character = match usr.character(CharacterId(q.cid)) {
         Some(c) => c,
         None => return (StatusCode::BAD_REQUEST, "Invalid character id").into_response(),
     };
-    match gen(
+    let options = match card_options(&s, q.rolls, q.total, q.grades, q.layout, q.style) {
+        Ok(o) => o,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    render(
+        &s,
         character.clone(),
-        &api,
-        &Lang::from(lang.as_str()),
+        &lang,
+        format,
+        counter,
+        options,
+        from_cache,
+    )
+    .await
+}
+
+async fn rolls(Query(q): Query<RollsQuery>, State(s): State<AppState>) -> impl IntoResponse {
+    log::info!("Rolls request {:?}", q);
+    let api = s.api;
+    let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
+    if !api.get_store().locale_list().contains(&&lang) {
+        return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
+    }
+    let usr = match api.simple(q.uid).await {
+        Ok((usr, _)) => usr,
+        Err(e) => return enka_error(e),
+    };
+    let character = match usr.character(CharacterId(q.cid)) {
+        Some(c) => c,
+        None => return (StatusCode::BAD_REQUEST, "Invalid character id").into_response(),
+    };
+    let artifacts = character
+        .reliquarys()
+        .iter()
+        .map(|r| ArtifactRolls {
+            position: format!("{:?}", r.position).to_lowercase(),
+            set_name: r.set_name(&api, &lang).unwrap_or_default(),
+            level: r.level,
+            substats: roll_breakdown(r)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|b| SubstatRolls {
+                    name: b.stat.name(&api, &lang).unwrap_or_default(),
+                    value: b.value,
+                    rolls: b.rolls(),
+                    values: b.values,
+                    tiers: b.tiers,
+                    roll_value: b.roll_value,
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    (StatusCode::OK, Json(artifacts)).into_response()
+}
+
+async fn generate_raw(
+    Query(q): Query<RawGenerateQuery>,
+    State(s): State<AppState>,
+    body: Bytes,
+) -> impl IntoResponse {
+    log::info!("Raw generate request {:?}", q);
+    let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
+    let format = match ImageFormat::from_str(&q.image_format.unwrap_or("png".to_string())) {
+        Ok(f) => f,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Invalid image format").into_response(),
+    };
+    let counter = match score_counter(q.counter, q.cid) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    if !s.api.get_store().locale_list().contains(&&lang) {
+        return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
+    }
+    let usr = match s.api.resolve_raw(body.to_vec()) {
+        Ok(usr) => usr,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    let character = match usr.character(CharacterId(q.cid)) {
+        Some(c) => c.clone(),
+        None => return (StatusCode::BAD_REQUEST, "Invalid character id").into_response(),
+    };
+    let options = match card_options(&s, q.rolls, q.total, q.grades, q.layout, q.style) {
+        Ok(o) => o,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    render(&s, character, &lang, format, counter, options, false).await
+}
+
+fn card_options(
+    s: &AppState,
+    rolls: Option<bool>,
+    total: Option<String>,
+    grades: Option<String>,
+    layout: Option<String>,
+    style: Option<String>,
+) -> Result<CardOptions, String> {
+    let layout = match layout.as_deref() {
+        None | Some("default") => None,
+        Some(name) => match s.layouts.get(name) {
+            Some(layout) => Some(layout),
+            None => return Err(format!("Invalid layout: {} is not a layout", name)),
+        },
+    };
+    let options = CardOptions {
+        roll_markers: rolls.unwrap_or(false),
+        total: match total {
+            Some(t) => TotalMetric::from_str(&t).map_err(|e| format!("Invalid total: {}", e))?,
+            None => TotalMetric::Score,
+        },
+        grades: grade_set(&GRADES.genshin, grades)?,
+        layout,
+        style: match style {
+            Some(s) => CardStyle::from_str(&s).map_err(|e| format!("Invalid style: {}", e))?,
+            None => CardStyle::default(),
+        },
+    };
+    options
+        .style
+        .check(&options)
+        .map_err(|e| format!("Invalid style: {}", e))?;
+    Ok(options)
+}
+
+/// Looks up the threshold set picked by the caller, if any.
+fn grade_set(
+    sets: &'static BTreeMap<String, GradeSet>,
+    name: Option<String>,
+) -> Result<Option<&'static GradeSet>, String> {
+    match name {
+        Some(n) => match sets.get(&n) {
+            Some(set) => Ok(Some(set)),
+            None => Err(format!("Invalid grades: {} is not a threshold set", n)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Unknown preset names fall back to the character default, but malformed
+/// custom weights are reported so callers can fix them.
+fn score_counter(counter: Option<String>, cid: u32) -> Result<ScoreCounter, String> {
+    match counter {
+        Some(c) => match ScoreCounter::from_str(&c) {
+            Ok(counter) => Ok(counter),
+            Err(e) if c.contains('=') => Err(format!("Invalid counter: {}", e)),
+            Err(_) => Ok(get_default(&cid)),
+        },
+        None => Ok(get_default(&cid)),
+    }
+}
+
+/// Same as `score_counter`, for HSR.
+fn hsr_score_counter(counter: Option<String>, cid: u32) -> Result<HsrScoreCounter, String> {
+    match counter {
+        Some(c) => match HsrScoreCounter::from_str(&c) {
+            Ok(counter) => Ok(counter),
+            Err(e) if c.contains('=') => Err(format!("Invalid counter: {}", e)),
+            Err(_) => Ok(get_hsr_default(&cid)),
+        },
+        None => Ok(get_hsr_default(&cid)),
+    }
+}
+
+/// Picks the ZZZ counter. It has no custom weights, so any unknown name is rejected.
+fn zzz_score_counter(counter: Option<String>, agent: &Agent) -> Result<ZzzScoreCounter, String> {
+    match counter {
+        Some(c) => ZzzScoreCounter::from_str(&c)
+            .map_err(|_| format!("Invalid counter: {} is not a score counter", c)),
+        None => Ok(ZzzScoreCounter::default_for(agent)),
+    }
+}
+
+async fn render(
+    s: &AppState,
+    character: Character,
+    lang: &str,
+    format: ImageFormat,
+    counter: ScoreCounter,
+    options: CardOptions,
+    from_cache: bool,
+) -> Response {
+    let now = Instant::now();
+    let metrics = ArtifactMetrics::of(&character);
+    let style = options.style;
+    match gen(
+        character,
+        &s.api,
+        &Lang::from(lang),
         &s.icons,
         counter,
         format.clone(),
+        options,
     )
     .await
     {
@@ -221,6 +682,16 @@ async fn generate(Query(q): Query<GenerateQuery>, State(s): State<AppState>) ->
             let mut headers = HeaderMap::new();
             headers.insert("X-From-Cache", from_cache.to_string().parse().unwrap());
             headers.insert("X-Score-Counter", counter.to_string().parse().unwrap());
+            // same definitions as `UserCharacter::crit_value` and `roll_value`
+            headers.insert(
+                "X-Crit-Value",
+                format!("{:.1}", metrics.crit_value).parse().unwrap(),
+            );
+            headers.insert(
+                "X-Roll-Value",
+                format!("{:.1}", metrics.roll_value).parse().unwrap(),
+            );
+            headers.insert("X-Card-Style", style.to_string().parse().unwrap());
             let mime = match format {
                 ImageFormat::Png => "image/png",
                 ImageFormat::Jpeg => "image/jpeg",
@@ -238,6 +709,143 @@ async fn generate(Query(q): Query<GenerateQuery>, State(s): State<AppState>) ->
     }
 }
 
+async fn user_character(
+    api: &Api,
+    c: &Character,
+    lang: &str,
+    format: &str,
+) -> Result<UserCharacter, &'static str> {
+    let icon = trim_image(c.image_icon(api).await, format);
+    let metrics = ArtifactMetrics::of(c);
+    Ok(UserCharacter {
+        ascension: c.ascension_level(),
+        level: c.level,
+        element_name: c.element.fight_prop_name().to_string(),
+        element: c.element.fight_prop_name().to_string(),
+        xp: c.xp,
+        name: c
+            .name(api, lang)
+            .map_err(|_| "Could not get character name correctly")?,
+        icon: icon.ok_or("Could not get character icon correctly")?,
+        id: c.id.0,
+        sets: c
+            .artifact_sets()
+            .iter()
+            .map(|s| ArtifactSet {
+                name: s.name(api, lang).unwrap_or_default(),
+                count: s.count,
+                bonuses: s.active_bonuses(),
+            })
+            .collect(),
+        crit_value: metrics.crit_value,
+        roll_value: metrics.roll_value,
+    })
+}
+
+async fn builds_profile(
+    Path(username): Path<String>,
+    State(s): State<AppState>,
+) -> impl IntoResponse {
+    log::info!("Builds profile request {}", username);
+    let profile = match s.api.profile(&username).await {
+        Ok(p) => p,
+        Err(e) => return enka_error(e),
+    };
+    let hoyos = match s.api.hoyos(&username).await {
+        Ok(h) => h,
+        Err(e) => return enka_error(e),
+    };
+    let profile = Profile {
+        username: profile.username,
+        bio: profile.bio,
+        level: profile.level,
+        hoyos: hoyos
+            .into_iter()
+            .map(|h| Hoyo {
+                name: h.player_info.nickname().clone(),
+                level: h.player_info.level(),
+                world_level: h.player_info.world_level(),
+                hash: h.hash,
+                uid: h.uid,
+                region: h.region,
+                verified: h.verified,
+            })
+            .collect(),
+    };
+    (StatusCode::OK, Json(profile)).into_response()
+}
+
+async fn builds(
+    Path((username, hash)): Path<(String, String)>,
+    Query(q): Query<BuildsQuery>,
+    State(s): State<AppState>,
+) -> impl IntoResponse {
+    log::info!("Builds request {}/{} {:?}", username, hash, q);
+    let api = s.api;
+    let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
+    let format = q.image_format.unwrap_or("png".to_string());
+    if !api.get_store().locale_list().contains(&&lang) {
+        return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
+    }
+    let list = match api.builds(&username, &hash).await {
+        Ok(b) => b,
+        Err(e) => return enka_error(e),
+    };
+    let mut builds = Vec::with_capacity(list.len());
+    for b in list {
+        let character = match user_character(&api, &b.character, &lang, &format).await {
+            Ok(c) => c,
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        };
+        builds.push(Build {
+            id: b.id,
+            name: b.name,
+            live: b.live,
+            character,
+        });
+    }
+    (StatusCode::OK, Json(builds)).into_response()
+}
+
+async fn build_generate(
+    Path((username, hash, id)): Path<(String, String, u64)>,
+    Query(q): Query<BuildGenerateQuery>,
+    State(s): State<AppState>,
+) -> impl IntoResponse {
+    log::info!(
+        "Build generate request {}/{}/{} {:?}",
+        username,
+        hash,
+        id,
+        q
+    );
+    let lang = Lang::from(q.lang.unwrap_or("en".to_string()).as_str()).to_string();
+    let format = match ImageFormat::from_str(&q.image_format.unwrap_or("png".to_string())) {
+        Ok(f) => f,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Invalid image format").into_response(),
+    };
+    if !s.api.get_store().locale_list().contains(&&lang) {
+        return (StatusCode::BAD_REQUEST, "Invalid language").into_response();
+    }
+    let build = match s.api.builds(&username, &hash).await {
+        Ok(b) => match b.into_iter().find(|b| b.id == id) {
+            Some(b) => b,
+            None => return (StatusCode::NOT_FOUND, "Build not found").into_response(),
+        },
+        Err(e) => return enka_error(e),
+    };
+    let cid = build.character.id.0;
+    let counter = match score_counter(q.counter, cid) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let options = match card_options(&s, q.rolls, q.total, q.grades, q.layout, q.style) {
+        Ok(o) => o,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    render(&s, build.character, &lang, format, counter, options, false).await
+}
+
 async fn hsr_profile(
     Query(q): Query<HsrProfileQuery>,
     State(s): State<HsrAppState>,
@@ -278,6 +886,16 @@ async fn hsr_profile(
                     },
                     path: c.path.id.clone(),
                     path_name: c.path.name.clone(),
+                    relic_sets: c
+                        .relic_sets
+                        .iter()
+                        .map(|s| HsrRelicSet {
+                            id: s.id.clone(),
+                            name: s.name.clone(),
+                            num: s.num,
+                            desc: s.desc.clone(),
+                        })
+                        .collect(),
                 });
             }
             let usr = HsrUser {
@@ -294,14 +912,55 @@ async fn hsr_profile(
             };
             (StatusCode::OK, Json(usr)).into_response()
         }
-        Err(_) => (
-            StatusCode::FAILED_DEPENDENCY,
-            "HoyoAPI has invalid response",
-        )
-            .into_response(),
+        Err(e) => mihomo_error(e),
     }
 }
 
+async fn hsr_rolls(
+    Query(q): Query<HsrRollsQuery>,
+    State(s): State<HsrAppState>,
+) -> impl IntoResponse {
+    log::info!("HSR Rolls request {:?}", q);
+    let lang = q.lang.unwrap_or("en".to_string());
+    let usr = match s.api.simple(q.uid, lang).await {
+        Ok((usr, _)) => usr,
+        Err(e) => return mihomo_error(e),
+    };
+    let character = match usr.characters.iter().find(|c| c.id == q.cid.to_string()) {
+        Some(c) => c,
+        None => return (StatusCode::BAD_REQUEST, "Invalid character id").into_response(),
+    };
+    let relics = character
+        .relics
+        .iter()
+        .map(|r| {
+            let rolls = HsrRelicRolls::of(r);
+            RelicRolls {
+                name: r.name.clone(),
+                set_name: r.set_name.clone(),
+                rarity: r.rarity,
+                level: r.level,
+                rolls: rolls.rolls,
+                efficiency: rolls.efficiency,
+                substats: rolls
+                    .substats
+                    .into_iter()
+                    .map(|s| HsrSubstatRolls {
+                        tiers: s.tiers(),
+                        name: s.name,
+                        field: s.field,
+                        display: s.display,
+                        rolls: s.rolls,
+                        steps: s.steps,
+                        efficiency: s.efficiency,
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    (StatusCode::OK, Json(relics)).into_response()
+}
+
 async fn hsr_generate(
     Query(q): Query<HsrGenerateQuery>,
     State(s): State<HsrAppState>,
@@ -314,10 +973,20 @@ async fn hsr_generate(
         Ok(f) => f,
         Err(_) => return (StatusCode::BAD_REQUEST, "Invalid image format").into_response(),
     };
-    let counter = if let Some(c) = q.counter {
-        HsrScoreCounter::from_str(&c).unwrap_or(HsrScoreCounter::Attack)
-    } else {
-        HsrScoreCounter::Attack
+    let counter = match hsr_score_counter(q.counter, q.cid) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let grades = match grade_set(&GRADES.hsr, q.grades) {
+        Ok(g) => g,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let style = match q.style.as_deref().map(HsrCardStyle::from_str) {
+        Some(Ok(s)) => s,
+        Some(Err(e)) => {
+            return (StatusCode::BAD_REQUEST, format!("Invalid style: {}", e)).into_response()
+        }
+        None => HsrCardStyle::default(),
     };
     let base_img = if let Some(b) = q.base_img {
         BaseImage::from_str(&b).unwrap_or(BaseImage::Belobog)
@@ -326,13 +995,7 @@ async fn hsr_generate(
     };
     let (usr, from_cache) = match api.simple(q.uid, lang.clone()).await {
         Ok((usr, from_cache)) => (usr, from_cache),
-        Err(_) => {
-            return (
-                StatusCode::FAILED_DEPENDENCY,
-                "Hoyo API had invalid response",
-            )
-                .into_response()
-        }
+        Err(e) => return mihomo_error(e),
     };
     let character = match usr.characters.iter().find(|c| c.id == q.cid.to_string()) {
         Some(c) => c,
@@ -345,6 +1008,11 @@ async fn hsr_generate(
         format.clone(),
         &lang,
         &counter,
+        HsrCardOptions {
+            roll_markers: q.rolls.unwrap_or(false),
+            grades,
+            style,
+        },
     )
     .await
     {
@@ -368,3 +1036,96 @@ async fn hsr_generate(
             .into_response(),
     }
 }
+
+async fn zzz_profile(
+    Query(q): Query<ZzzProfileQuery>,
+    State(s): State<ZzzAppState>,
+) -> impl IntoResponse {
+    log::info!("ZZZ Profile request {:?}", q);
+    let api = s.api;
+    let lang = q.lang.unwrap_or("en".to_string());
+    let format = q.image_format.unwrap_or("png".to_string());
+    match api.simple(q.uid, lang.to_string()).await {
+        Ok((usr, from_cache)) => {
+            let mut characters = Vec::with_capacity(usr.agents.len());
+            for a in &usr.agents {
+                let icon = trim_image(api.asset(&a.image).await.ok(), &format);
+                characters.push(ZzzUserCharacter {
+                    level: a.level,
+                    mindscape: a.mindscape,
+                    profession: a.profession.clone(),
+                    element: a.element.map(|e| e.to_string()),
+                    name: a.name.clone(),
+                    icon: match icon {
+                        Some(i) => i,
+                        None => {
+                            return (
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                "Could not get character icon correctly",
+                            )
+                                .into_response()
+                        }
+                    },
+                    id: a.id,
+                });
+            }
+            let usr = ZzzUser {
+                level: usr.level,
+                uid: usr.uid,
+                name: usr.name.clone(),
+                description: usr.description.clone(),
+                from_cache,
+                characters,
+                lastupdate: usr.lastupdate.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            };
+            (StatusCode::OK, Json(usr)).into_response()
+        }
+        Err(e) => zzz_error(e),
+    }
+}
+
+async fn zzz_generate(
+    Query(q): Query<ZzzGenerateQuery>,
+    State(s): State<ZzzAppState>,
+) -> impl IntoResponse {
+    log::info!("ZZZ Generate request {:?}", q);
+    let now = Instant::now();
+    let api = s.api;
+    let lang = q.lang.unwrap_or("en".to_string());
+    let format = match ZzzImageFormat::from_str(&q.image_format.unwrap_or("png".to_string())) {
+        Ok(f) => f,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Invalid image format").into_response(),
+    };
+    let (usr, from_cache) = match api.simple(q.uid, lang.clone()).await {
+        Ok((usr, from_cache)) => (usr, from_cache),
+        Err(e) => return zzz_error(e),
+    };
+    let agent = match usr.agents.iter().find(|a| a.id == q.cid) {
+        Some(a) => a,
+        None => return (StatusCode::BAD_REQUEST, "Invalid character id").into_response(),
+    };
+    let counter = match zzz_score_counter(q.counter, agent) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    match zzz_gen(&api, agent, format.clone(), &lang, &counter).await {
+        Some(img) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("X-From-Cache", from_cache.to_string().parse().unwrap());
+            headers.insert("X-Score-Counter", counter.to_string().parse().unwrap());
+            let mime = match format {
+                ZzzImageFormat::Png => "image/png",
+                ZzzImageFormat::Jpeg => "image/jpeg",
+                _ => "image/raw",
+            };
+            headers.insert("Content-Type", mime.parse().unwrap());
+            log::info!("Generated image in {}ms", now.elapsed().as_millis());
+            (StatusCode::OK, headers, img).into_response()
+        }
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Could not generate image",
+        )
+            .into_response(),
+    }
+}
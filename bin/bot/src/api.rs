@@ -1,9 +1,35 @@
-use std::env;
+use std::{env, fmt::Display};
 
 use apitype::genshin::User;
 use apitype::hsr::User as HsrUser;
+use apitype::zzz::User as ZzzUser;
 use reqwest::{header::HeaderMap, Client};
 
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.status)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Score counter the card was scored with, lowercased. Falls back to the
+/// requested one when the API did not send a usable `X-Score-Counter` header.
+fn score_counter(headers: &HeaderMap, requested: Option<&str>) -> String {
+    headers
+        .get("X-Score-Counter")
+        .and_then(|h| h.to_str().ok())
+        .or(requested)
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
 #[derive(Clone)]
 pub struct Api {
     pub client: Client,
@@ -31,11 +57,9 @@ impl Api {
         let res = self.client.get(url).send().await?;
         let headers = res.headers().clone();
         if res.status().is_client_error() || res.status().is_server_error() {
-            let err = res.text().await?;
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                err,
-            )));
+            let status = res.status().as_u16();
+            let message = res.text().await?;
+            return Err(Box::new(ApiError { status, message }));
         }
         Ok((res.bytes().await?.to_vec(), headers))
     }
@@ -72,6 +96,7 @@ impl Api {
         character: String,
         score: Option<String>,
         format: String,
+        style: Option<String>,
     ) -> Result<(Vec<u8>, String), Box<dyn std::error::Error + Send + Sync>> {
         let mut params = vec![
             ("uid".to_string(), uid),
@@ -79,18 +104,14 @@ impl Api {
             ("image_format".to_string(), format),
             ("lang".to_string(), lang.unwrap_or_else(|| "en".to_string())),
         ];
-        if let Some(score) = score {
-            params.push(("counter".to_string(), score));
+        if let Some(score) = &score {
+            params.push(("counter".to_string(), score.clone()));
+        }
+        if let Some(style) = style {
+            params.push(("style".to_string(), style));
         }
         let (buf, h) = self.request("generate".to_string(), params).await?;
-        Ok((
-            buf,
-            h.get("X-Score-Counter")
-                .unwrap()
-                .to_str()?
-                .to_string()
-                .to_lowercase(),
-        ))
+        Ok((buf, score_counter(&h, score.as_deref())))
     }
     pub async fn hsr_generate(
         &self,
@@ -110,17 +131,43 @@ impl Api {
         if let Some(base_img) = base_img {
             params.push(("base_img".to_string(), base_img));
         }
-        if let Some(score) = score {
-            params.push(("counter".to_string(), score));
+        if let Some(score) = &score {
+            params.push(("counter".to_string(), score.clone()));
         }
         let (buf, h) = self.request("hsr/generate".to_string(), params).await?;
-        Ok((
-            buf,
-            h.get("X-Score-Counter")
-                .unwrap()
-                .to_str()?
-                .to_string()
-                .to_lowercase(),
-        ))
+        Ok((buf, score_counter(&h, score.as_deref())))
+    }
+    pub async fn zzz_profile(
+        &self,
+        uid: String,
+        lang: Option<String>,
+    ) -> Result<ZzzUser, Box<dyn std::error::Error + Send + Sync>> {
+        let mut params = vec![("uid".to_string(), uid)];
+        if let Some(lang) = lang {
+            params.push(("lang".to_string(), lang));
+        }
+        let (res, _h) = self.request("zzz/profile".to_string(), params).await?;
+        let user = serde_json::from_slice(&res)?;
+        Ok(user)
+    }
+    pub async fn zzz_generate(
+        &self,
+        lang: Option<String>,
+        uid: String,
+        character: String,
+        score: Option<String>,
+        format: String,
+    ) -> Result<(Vec<u8>, String), Box<dyn std::error::Error + Send + Sync>> {
+        let mut params = vec![
+            ("uid".to_string(), uid),
+            ("cid".to_string(), character),
+            ("image_format".to_string(), format),
+            ("lang".to_string(), lang.unwrap_or_else(|| "en".to_string())),
+        ];
+        if let Some(score) = &score {
+            params.push(("counter".to_string(), score.clone()));
+        }
+        let (buf, h) = self.request("zzz/generate".to_string(), params).await?;
+        Ok((buf, score_counter(&h, score.as_deref())))
     }
 }
@@ -15,10 +15,12 @@ impl Cache {
             format: "png".to_string(),
             score: None,
             character: None,
+            style: None,
         });
         if let Some(value) = self.inner.get_mut(&uid) {
             match k.as_str() {
                 "format" => value.format = v,
+                "style" => value.style = Some(v),
                 "score" => value.score = Some(v),
                 "character" => {
                     value.character = Some(v);
@@ -41,6 +43,7 @@ pub struct CacheValue {
     pub format: String,
     pub score: Option<String>,
     pub character: Option<String>,
+    pub style: Option<String>,
 }
 
 pub struct HsrCache {
@@ -88,3 +91,46 @@ pub struct HsrCacheValue {
     pub character: Option<String>,
     pub base_img: Option<String>,
 }
+
+pub struct ZzzCache {
+    inner: BTreeMap<i32, ZzzCacheValue>,
+}
+
+impl ZzzCache {
+    pub fn new() -> Self {
+        Self {
+            inner: BTreeMap::new(),
+        }
+    }
+    pub fn update(&mut self, uid: i32, k: String, v: String) {
+        self.inner.entry(uid).or_insert_with(|| ZzzCacheValue {
+            format: "png".to_string(),
+            score: None,
+            character: None,
+        });
+        if let Some(value) = self.inner.get_mut(&uid) {
+            match k.as_str() {
+                "zzz_format" => value.format = v,
+                "zzz_score" => value.score = Some(v),
+                "zzz_character" => {
+                    value.character = Some(v);
+                    value.score = None;
+                }
+                _ => {}
+            };
+        }
+    }
+
+    pub fn get_or_default(&self, uid: i32) -> Option<&ZzzCacheValue> {
+        if let Some(value) = self.inner.get(&uid) {
+            return Some(value);
+        }
+        None
+    }
+}
+
+pub struct ZzzCacheValue {
+    pub format: String,
+    pub score: Option<String>,
+    pub character: Option<String>,
+}
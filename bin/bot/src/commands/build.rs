@@ -5,6 +5,7 @@ use crate::component::profile_components;
 use crate::db::find_genshin;
 use crate::state::{Context, Error};
 use crate::tips::filter_tips;
+use crate::util::fetch_error_message;
 
 /// fetch data from User Id
 #[poise::command(
@@ -45,8 +46,8 @@ pub async fn build(
         .await
     {
         Ok(user) => user,
-        Err(_) => {
-            ctx.send(CreateReply::new().content(t!(locale, "main:general.failedToFetchData")))
+        Err(e) => {
+            ctx.send(CreateReply::new().content(fetch_error_message(locale, &e)))
                 .await?;
             return Ok(());
         }
@@ -5,6 +5,7 @@ use poise::CreateReply;
 use crate::component::profile_components;
 use crate::db::find_genshin;
 use crate::state::{Context, Error};
+use crate::util::fetch_error_message;
 use serenity::User;
 
 /// get user's profile
@@ -35,8 +36,8 @@ pub async fn genshin_profile(ctx: Context<'_>, user: User) -> Result<(), Error>
         .await
     {
         Ok(user) => user,
-        Err(_) => {
-            ctx.send(CreateReply::new().content(t!(locale, "main:general.failedToFetchData")))
+        Err(e) => {
+            ctx.send(CreateReply::new().content(fetch_error_message(locale, &e)))
                 .await?;
             return Ok(());
         }
@@ -8,7 +8,9 @@ mod hsr_profile;
 mod hsr_unlink;
 mod link;
 mod unlink;
+mod zzz;
 mod zzz_link;
+mod zzz_profile;
 mod zzz_unlink;
 use crate::state::{Error, State};
 
@@ -25,5 +27,7 @@ pub fn load() -> Vec<Command<State, Error>> {
         about::about(),
         zzz_link::zzz_link(),
         zzz_unlink::zzz_unlink(),
+        zzz_profile::zzz_profile(),
+        zzz::zzz(),
     ]
 }
@@ -0,0 +1,69 @@
+use localization::t;
+use poise::CreateReply;
+
+use crate::db::find_zzz;
+use crate::state::{Context, Error};
+use crate::tips::filter_tips;
+use crate::zzz_components::zzz_profile_components;
+
+/// fetch data from User Id
+#[poise::command(
+    slash_command,
+    description_localized("ja", "UIDからデータを取得します")
+)]
+pub async fn zzz(
+    ctx: Context<'_>,
+    #[description = "UID"]
+    #[description_localized("ja", "ユーザーID")]
+    uid: Option<i32>,
+) -> Result<(), Error> {
+    let locale = ctx.locale().unwrap_or("ja");
+    let tips = vec![t!(locale, "main:tips.supportServer")];
+    let displayed_tip = filter_tips(tips, t!(locale, "main:tips.zzzLink"), uid.is_some());
+    let data = ctx.data();
+    let mut uid = uid.map(|u| u.to_string());
+    if uid.is_none() {
+        if let Ok(Some(db_uid)) = find_zzz(&data.db, ctx.author().id.get()).await {
+            uid = Some(db_uid);
+        }
+        if uid.is_none() {
+            ctx.send(CreateReply::new().content(t!(locale, "main:general.noUserId")))
+                .await?;
+            return Ok(());
+        }
+    }
+    let uid = uid.unwrap();
+    if !(8..=10).contains(&uid.to_string().len()) {
+        ctx.send(CreateReply::new().content(t!(locale, "main:general.userIdMustBeNineDigits")))
+            .await?;
+        return Ok(());
+    }
+    ctx.defer().await?;
+    let user = match data
+        .api
+        .zzz_profile(uid.clone(), Some(locale.to_string()))
+        .await
+    {
+        Ok(user) => user,
+        Err(_) => {
+            ctx.send(CreateReply::new().content(t!(locale, "main:general.failedToFetchData")))
+                .await?;
+            return Ok(());
+        }
+    };
+    if user.characters.is_empty() {
+        ctx.send(CreateReply::new().content(t!(locale, "main:general.noCharacters")))
+            .await?;
+        return Ok(());
+    }
+    let (embed, components, attachment) = zzz_profile_components(locale.to_string(), uid, user);
+    let mut builder = CreateReply::default().components(components).embed(embed);
+    if let Some(attachment) = attachment {
+        builder = builder.attachment(attachment);
+    }
+    if let Some(tip) = displayed_tip {
+        builder = builder.content(tip);
+    }
+    ctx.send(builder).await?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use localization::t;
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+
+use crate::db::find_zzz;
+use crate::state::{Context, Error};
+use crate::zzz_components::zzz_profile_components;
+use serenity::User;
+
+/// get user's profile
+#[poise::command(
+    context_menu_command = "Get ZZZ Info",
+    description_localized("ja", "プロフィールを表示します"),
+    slash_command
+)]
+pub async fn zzz_profile(ctx: Context<'_>, user: User) -> Result<(), Error> {
+    let locale = ctx.locale().unwrap_or("ja");
+    let data = ctx.data();
+    let uid = if let Ok(Some(db_uid)) = find_zzz(&data.db, user.id.get()).await {
+        db_uid
+    } else {
+        ctx.send(CreateReply::new().content(t!(locale, "main:general.noUserId")))
+            .await?;
+        return Ok(());
+    };
+    if !(8..=10).contains(&uid.to_string().len()) {
+        ctx.send(CreateReply::new().content(t!(locale, "main:general.userIdMustBeNineDigits")))
+            .await?;
+        return Ok(());
+    }
+    ctx.defer().await?;
+    let user = match data
+        .api
+        .zzz_profile(uid.clone(), Some(locale.to_string()))
+        .await
+    {
+        Ok(user) => user,
+        Err(_) => {
+            ctx.send(CreateReply::new().content(t!(locale, "main:general.failedToFetchData")))
+                .await?;
+            return Ok(());
+        }
+    };
+    if user.characters.is_empty() {
+        ctx.send(CreateReply::new().content(t!(locale, "main:general.noCharacters")))
+            .await?;
+        return Ok(());
+    }
+    let (embed, components, attachment) = zzz_profile_components(locale.to_string(), uid, user);
+    let mut builder = CreateReply::default().components(components).embed(embed);
+    if let Some(attachment) = attachment {
+        builder = builder.attachment(attachment);
+    }
+    ctx.send(builder).await?;
+    Ok(())
+}
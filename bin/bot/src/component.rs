@@ -34,6 +34,7 @@ pub async fn generate_components<'a>(
                 cid.clone(),
                 cache.score.clone(),
                 cache.format.clone(),
+                cache.style.clone(),
             )
             .await
         {
@@ -186,6 +187,22 @@ pub fn create_components<'a>(
     .min_values(1)
     .placeholder(t!(locale, "main:general.selectFileFormat"));
     let format = CreateActionRow::SelectMenu(format);
+    let style = CreateSelectMenu::new(
+        "style",
+        CreateSelectMenuKind::String {
+            options: [
+                (t!(locale, "main:cardStyle.classic"), "classic"),
+                (t!(locale, "main:cardStyle.compact"), "compact"),
+            ]
+            .iter()
+            .map(|x| CreateSelectMenuOption::new(x.0.clone(), x.1))
+            .collect(),
+        },
+    )
+    .max_values(1)
+    .min_values(1)
+    .placeholder(t!(locale, "main:general.selectStyle"));
+    let style = CreateActionRow::SelectMenu(style);
     let button = CreateActionRow::Buttons(vec![
         CreateButton::new_link(format!("https://enka.network/u/{}", uid)).label("Enka Network"),
         CreateButton::new_link(format!(
@@ -197,5 +214,5 @@ pub fn create_components<'a>(
         .style(ButtonStyle::Danger)
         .label(t!(locale, "main:general.close")),*/
     ]);
-    vec![chara, score, format, button]
+    vec![chara, score, format, style, button]
 }
@@ -8,6 +8,7 @@ use serenity::{
 
 use crate::component::generate_components;
 use crate::hsr_components::{hsr_generate_components, hsr_profile_components};
+use crate::zzz_components::{zzz_generate_components, zzz_profile_components};
 use crate::{
     component::profile_components,
     state::{Error, State},
@@ -38,7 +39,11 @@ pub async fn handler(
         let custom_id = select_menu.data.custom_id.clone();
         let lang = select_menu.locale.to_string();
         if let ComponentInteractionDataKind::StringSelect { values } = &select_menu.data.kind {
-            if &custom_id == "character" || &custom_id == "score" || &custom_id == "format" {
+            if &custom_id == "character"
+                || &custom_id == "score"
+                || &custom_id == "format"
+                || &custom_id == "style"
+            {
                 select_menu.defer(&ctx.http).await?;
                 let uid = match message(&select_menu.message.embeds) {
                     Some(uid) => uid,
@@ -140,6 +145,58 @@ pub async fn handler(
                     }
                     select_menu.edit_response(&ctx.http, builder).await?;
                 }
+            } else if &custom_id == "zzz_character"
+                || &custom_id == "zzz_score"
+                || &custom_id == "zzz_format"
+            {
+                select_menu.defer(&ctx.http).await?;
+                let uid = match message(&select_menu.message.embeds) {
+                    Some(uid) => uid,
+                    None => {
+                        select_menu
+                            .create_response(
+                                &ctx.http,
+                                CreateInteractionResponse::Message(
+                                    CreateInteractionResponseMessage::new()
+                                        .content(t!(lang, "main:general.parseFailed")),
+                                ),
+                            )
+                            .await?;
+                        return Ok(());
+                    }
+                };
+                let user = state
+                    .api
+                    .zzz_profile(uid.to_string(), Some(lang.clone()))
+                    .await?;
+                let mut cache = state.zzz_cache.lock().await;
+                if let Some(value) = values.first() {
+                    cache.update(uid, custom_id.to_string(), value.to_string());
+                };
+                if let Some(value) = cache.get_or_default(uid) {
+                    if let Some((embed, components, attachment)) =
+                        zzz_generate_components(lang, uid.to_string(), user, value, &state.api)
+                            .await
+                    {
+                        let mut builder = EditInteractionResponse::new()
+                            .components(components)
+                            .embed(embed);
+                        if let Some(attachment) = attachment {
+                            builder = builder.new_attachment(attachment);
+                        }
+                        select_menu.edit_response(&ctx.http, builder).await?;
+                    };
+                } else {
+                    let (embed, components, attachment) =
+                        zzz_profile_components(lang, uid.to_string(), user);
+                    let mut builder = EditInteractionResponse::new()
+                        .components(components)
+                        .embed(embed);
+                    if let Some(attachment) = attachment {
+                        builder = builder.new_attachment(attachment);
+                    }
+                    select_menu.edit_response(&ctx.http, builder).await?;
+                }
             }
         }
     }
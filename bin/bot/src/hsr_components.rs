@@ -149,6 +149,16 @@ pub fn create_components<'a>(
                 (t!(locale, "main:hsrCalculationMethod.speed"), "speed"),
                 (t!(locale, "main:hsrCalculationMethod.hpOnly"), "hp_only"),
                 (t!(locale, "main:hsrCalculationMethod.ehrOnly"), "ehr_only"),
+                (t!(locale, "main:hsrCalculationMethod.res"), "res"),
+                (t!(locale, "main:hsrCalculationMethod.healing"), "healing"),
+                (
+                    t!(locale, "main:hsrCalculationMethod.energyRegen"),
+                    "energy_regen",
+                ),
+                (
+                    t!(locale, "main:hsrCalculationMethod.critOnly"),
+                    "crit_only",
+                ),
             ]
             .iter()
             .map(|x| CreateSelectMenuOption::new(x.0.clone(), x.1))
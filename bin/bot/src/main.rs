@@ -14,6 +14,7 @@ mod logger;
 mod state;
 mod tips;
 mod util;
+mod zzz_components;
 
 #[tokio::main]
 async fn main() {
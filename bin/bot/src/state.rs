@@ -4,7 +4,7 @@ use tokio::sync::Mutex;
 
 use crate::{
     api::Api,
-    cache::{Cache, HsrCache},
+    cache::{Cache, HsrCache, ZzzCache},
     db::{connect, PgPool},
 };
 
@@ -15,6 +15,7 @@ pub struct State {
     pub api: Api,
     pub cache: Arc<Mutex<Cache>>,
     pub hsr_cache: Arc<Mutex<HsrCache>>,
+    pub zzz_cache: Arc<Mutex<ZzzCache>>,
 }
 
 impl State {
@@ -27,6 +28,7 @@ impl State {
             api: Api::new(),
             cache: Arc::new(Mutex::new(Cache::new())),
             hsr_cache: Arc::new(Mutex::new(HsrCache::new())),
+            zzz_cache: Arc::new(Mutex::new(ZzzCache::new())),
         }
     }
 }
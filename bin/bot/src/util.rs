@@ -1,10 +1,23 @@
 use localization::t;
 
+use crate::{api::ApiError, state::Error};
+
 pub fn convert_rgb(rgb: [u8; 3]) -> u32 {
     let [r, g, b] = rgb;
     (r as u32) << 16 | (g as u32) << 8 | b as u32
 }
 
+/// Explains why fetching a Genshin profile failed, based on the status returned by the API.
+pub fn fetch_error_message(lang: &str, e: &Error) -> String {
+    match e.downcast_ref::<ApiError>().map(|e| e.status) {
+        Some(400) => t!(lang, "main:general.invalidUserId"),
+        Some(404) => t!(lang, "main:general.userNotFound"),
+        Some(424) => t!(lang, "main:general.maintenance"),
+        Some(429) => t!(lang, "main:general.rateLimited"),
+        _ => t!(lang, "main:general.failedToFetchData"),
+    }
+}
+
 pub enum ScoreCounter {
     Normal,
     Hp,
@@ -25,6 +38,13 @@ pub enum HsrScoreCounter {
     EhrOnly,
 }
 
+pub enum ZzzScoreCounter {
+    Attack,
+    Hp,
+    Defense,
+    Anomaly,
+}
+
 impl From<&str> for ScoreCounter {
     fn from(s: &str) -> Self {
         match s {
@@ -55,6 +75,18 @@ impl From<&str> for HsrScoreCounter {
     }
 }
 
+impl From<&str> for ZzzScoreCounter {
+    fn from(s: &str) -> Self {
+        match s {
+            "attack" => ZzzScoreCounter::Attack,
+            "hp" => ZzzScoreCounter::Hp,
+            "defense" => ZzzScoreCounter::Defense,
+            "anomaly" => ZzzScoreCounter::Anomaly,
+            _ => ZzzScoreCounter::Attack,
+        }
+    }
+}
+
 pub fn get_score_calc(lang: String, s: impl Into<ScoreCounter>) -> String {
     match s.into() {
         ScoreCounter::Normal => {
@@ -106,3 +138,20 @@ pub fn get_hsr_score_calc(lang: String, s: impl Into<HsrScoreCounter>) -> String
         }
     }
 }
+
+pub fn get_zzz_score_calc(lang: String, s: impl Into<ZzzScoreCounter>) -> String {
+    match s.into() {
+        ZzzScoreCounter::Attack => {
+            t!(lang, "main:zzzCalculationFormula.attack")
+        }
+        ZzzScoreCounter::Hp => {
+            t!(lang, "main:zzzCalculationFormula.hp")
+        }
+        ZzzScoreCounter::Defense => {
+            t!(lang, "main:zzzCalculationFormula.defense")
+        }
+        ZzzScoreCounter::Anomaly => {
+            t!(lang, "main:zzzCalculationFormula.anomaly")
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::borrow::Cow;
+
+use apitype::zzz::{User, UserCharacter};
+use localization::t;
+use poise::serenity_prelude as serenity;
+use serenity::{
+    CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, Timestamp,
+};
+
+use crate::{
+    api::Api,
+    cache::ZzzCacheValue,
+    util::{convert_rgb, get_zzz_score_calc},
+};
+
+pub async fn zzz_generate_components(
+    locale: String,
+    uid: String,
+    user: User,
+    cache: &ZzzCacheValue,
+    api: &Api,
+) -> Option<(
+    CreateEmbed<'static>,
+    Vec<CreateActionRow<'static>>,
+    Option<CreateAttachment<'static>>,
+)> {
+    if let Some(cid) = cache.character.clone() {
+        if let Ok((img, score)) = api
+            .zzz_generate(
+                Some(locale.clone()),
+                uid.clone(),
+                cid.clone(),
+                cache.score.clone(),
+                cache.format.clone(),
+            )
+            .await
+        {
+            let components =
+                create_components(user.characters.clone(), locale.clone(), uid.clone());
+            let attachment = Some(CreateAttachment::bytes(img, "image.png"));
+            let name = user
+                .characters
+                .iter()
+                .find(|x| x.id.to_string() == cid)?
+                .name
+                .clone();
+            let embed = CreateEmbed::new()
+                .title(t!(locale, "main:general.generated", name))
+                .footer(CreateEmbedFooter::new(uid.to_string()))
+                .color(convert_rgb([0x00, 0xff, 0x00]))
+                .description(format!(
+                    "{}{}",
+                    get_zzz_score_calc(locale.clone(), score.as_str()),
+                    if user.from_cache {
+                        format!("\n{}", t!(locale, "main:general.isCached"))
+                    } else {
+                        "".to_string()
+                    }
+                ))
+                .image("attachment://image.png")
+                .timestamp(Timestamp::from_unix_timestamp(user.lastupdate as i64).unwrap());
+            return Some((embed, components, attachment));
+        }
+    }
+    log::info!("Failed to generate image");
+    None
+}
+
+pub fn zzz_profile_components<'a>(
+    locale: String,
+    uid: String,
+    user: User,
+) -> (
+    CreateEmbed<'a>,
+    Vec<CreateActionRow<'a>>,
+    Option<CreateAttachment<'a>>,
+) {
+    let footer = CreateEmbedFooter::new(uid.to_string());
+    let embed = CreateEmbed::default()
+        .title(format!("{}({})", user.name, user.level))
+        .footer(footer)
+        .color(convert_rgb([0x00, 0xff, 0x00]))
+        .description(format!(
+            "{}{}",
+            user.description,
+            if user.from_cache {
+                t!(locale, "main:general.isCached")
+            } else {
+                "".to_string()
+            }
+        ))
+        .timestamp(Timestamp::from_unix_timestamp(user.lastupdate as i64).unwrap());
+    let components = create_components(user.characters, locale, uid);
+    (embed, components, None)
+}
+
+pub fn create_components<'a>(
+    characters: Vec<UserCharacter>,
+    locale: String,
+    uid: String,
+) -> Vec<CreateActionRow<'a>> {
+    let mut options = Vec::<CreateSelectMenuOption>::new();
+    for character in characters {
+        options.push(
+            CreateSelectMenuOption::new(character.name, format!("{}", &character.id))
+                .description(format!("{}Lv", character.level)),
+        )
+    }
+    let chara = CreateSelectMenu::new(
+        "zzz_character",
+        CreateSelectMenuKind::String {
+            options: Cow::Owned(options),
+        },
+    )
+    .placeholder(t!(locale, "main:general.selectCharacter"))
+    .max_values(1)
+    .min_values(1);
+    let chara = CreateActionRow::SelectMenu(chara);
+    let score = CreateSelectMenu::new(
+        "zzz_score",
+        CreateSelectMenuKind::String {
+            options: [
+                (t!(locale, "main:zzzCalculationMethod.attack"), "attack"),
+                (t!(locale, "main:zzzCalculationMethod.defense"), "defense"),
+                (t!(locale, "main:zzzCalculationMethod.hp"), "hp"),
+                (t!(locale, "main:zzzCalculationMethod.anomaly"), "anomaly"),
+            ]
+            .iter()
+            .map(|x| CreateSelectMenuOption::new(x.0.clone(), x.1))
+            .collect(),
+        },
+    )
+    .max_values(1)
+    .min_values(1)
+    .placeholder(t!(locale, "main:general.selectCalculationMethod"));
+    let score = CreateActionRow::SelectMenu(score);
+    let format = CreateSelectMenu::new(
+        "zzz_format",
+        CreateSelectMenuKind::String {
+            options: [("PNG", "png"), ("JPEG", "jpeg")]
+                .iter()
+                .map(|x| CreateSelectMenuOption::new(x.0, x.1))
+                .collect(),
+        },
+    )
+    .max_values(1)
+    .min_values(1)
+    .placeholder(t!(locale, "main:general.selectFileFormat"));
+    let format = CreateActionRow::SelectMenu(format);
+    let button = CreateActionRow::Buttons(vec![CreateButton::new_link(format!(
+        "https://enka.network/zzz/{}",
+        uid
+    ))
+    .label("Enka Network")]);
+    vec![chara, score, format, button]
+}
@@ -33,6 +33,25 @@ pub struct UserCharacter {
     pub name: String,
     pub icon: String,
     pub id: u32,
+    /// Artifact sets with an active bonus, most pieces first.
+    #[serde(default)]
+    pub sets: Vec<ArtifactSet>,
+    /// Crit rate * 2 + crit damage over all artifact substats.
+    #[serde(default)]
+    pub crit_value: f64,
+    /// Substat rolls of the 5-star artifacts, each substat counted in max rolls, as
+    /// a percentage of the most rolls they could have at their levels (4 plus one
+    /// per 4 levels). Other rarities are skipped.
+    #[serde(default)]
+    pub roll_value: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArtifactSet {
+    pub name: String,
+    pub count: u8,
+    /// Active piece bonuses, e.g. `[2, 4]`.
+    pub bonuses: Vec<u8>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -41,5 +60,121 @@ pub struct GenerateQuery {
     pub lang: Option<String>,
     pub image_format: Option<String>,
     pub cid: u32,
+    /// Score counter name, or custom `key=weight` pairs such as `crit=1,atk%=0.75`.
+    /// Keys are `hp`, `atk`, `def`, `hp%`, `atk%`, `def%`, `cr`, `cd`, `er` and `em`,
+    /// and `crit=w` means `cr=w,cd=w`. Weights count rolls: one max 5-star roll at
+    /// weight 1 scores 7.8, a max CRIT roll under the presets.
     pub counter: Option<String>,
+    /// Draws roll-tier markers next to each substat.
+    pub rolls: Option<bool>,
+    /// Metric shown in the total score panel: `score` (default), `cv` or `rv`.
+    pub total: Option<String>,
+    /// Grade threshold set, defaults to the one of the score counter.
+    pub grades: Option<String>,
+    /// Card layout name, defaults to the built-in layout.
+    pub layout: Option<String>,
+    /// Card style: `classic` (default) or `compact`, which takes no `layout` or `rolls`.
+    pub style: Option<String>,
+}
+
+/// Query of `POST /generate`, whose body is a raw Enka.Network user payload.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawGenerateQuery {
+    pub lang: Option<String>,
+    pub image_format: Option<String>,
+    pub cid: u32,
+    /// Score counter name, or custom `key=weight` pairs such as `crit=1,atk%=0.75`.
+    /// Keys are `hp`, `atk`, `def`, `hp%`, `atk%`, `def%`, `cr`, `cd`, `er` and `em`,
+    /// and `crit=w` means `cr=w,cd=w`. Weights count rolls: one max 5-star roll at
+    /// weight 1 scores 7.8, a max CRIT roll under the presets.
+    pub counter: Option<String>,
+    /// Draws roll-tier markers next to each substat.
+    pub rolls: Option<bool>,
+    /// Metric shown in the total score panel: `score` (default), `cv` or `rv`.
+    pub total: Option<String>,
+    /// Grade threshold set, defaults to the one of the score counter.
+    pub grades: Option<String>,
+    /// Card layout name, defaults to the built-in layout.
+    pub layout: Option<String>,
+    /// Card style: `classic` (default) or `compact`, which takes no `layout` or `rolls`.
+    pub style: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BuildsQuery {
+    pub lang: Option<String>,
+    pub image_format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Profile {
+    pub username: String,
+    pub bio: String,
+    pub level: u32,
+    pub hoyos: Vec<Hoyo>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Hoyo {
+    pub hash: String,
+    pub uid: Option<i32>,
+    pub region: String,
+    pub verified: bool,
+    pub name: String,
+    pub level: u8,
+    pub world_level: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Build {
+    pub id: u64,
+    pub name: String,
+    pub live: bool,
+    pub character: UserCharacter,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BuildGenerateQuery {
+    pub lang: Option<String>,
+    pub image_format: Option<String>,
+    /// Score counter name or custom weights, as in `GenerateQuery::counter`.
+    pub counter: Option<String>,
+    /// Draws roll-tier markers next to each substat.
+    pub rolls: Option<bool>,
+    /// Metric shown in the total score panel: `score` (default), `cv` or `rv`.
+    pub total: Option<String>,
+    /// Grade threshold set, defaults to the one of the score counter.
+    pub grades: Option<String>,
+    /// Card layout name, defaults to the built-in layout.
+    pub layout: Option<String>,
+    /// Card style: `classic` (default) or `compact`, which takes no `layout` or `rolls`.
+    pub style: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RollsQuery {
+    pub uid: i32,
+    pub cid: u32,
+    pub lang: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArtifactRolls {
+    pub position: String,
+    pub set_name: String,
+    pub level: u8,
+    /// Empty for artifacts below 5 stars, which cannot be broken down.
+    pub substats: Vec<SubstatRolls>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubstatRolls {
+    pub name: String,
+    pub value: f64,
+    pub rolls: usize,
+    pub values: Vec<f64>,
+    /// 1 (lowest) to 4 (max) per roll.
+    pub tiers: Vec<u8>,
+    /// Sum of the rolls as a percentage of one max roll.
+    pub roll_value: f64,
 }
@@ -31,6 +31,18 @@ pub struct UserCharacter {
     pub name: String,
     pub icon: String,
     pub id: u32,
+    /// Active relic and planar set bonuses, one entry per bonus.
+    #[serde(default)]
+    pub relic_sets: Vec<RelicSet>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RelicSet {
+    pub id: String,
+    pub name: String,
+    /// Pieces needed for this bonus, 2 or 4.
+    pub num: u8,
+    pub desc: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -39,6 +51,49 @@ pub struct GenerateQuery {
     pub lang: Option<String>,
     pub image_format: Option<String>,
     pub cid: u32,
+    /// Score counter name, or custom `key=weight` pairs such as `crit=1,spd=1`.
+    /// Keys are `hp`, `atk`, `def`, `hp%`, `atk%`, `def%`, `cr`, `cd`, `spd`, `ehr`,
+    /// `res` and `be`, and `crit=w` means `cr=w,cd=w`. Weights count rolls: one max
+    /// 5-star roll at weight 1 scores 6.48, a max CRIT roll under the presets.
     pub counter: Option<String>,
     pub base_img: Option<String>,
+    /// Grade threshold set, defaults to the one of the score counter.
+    pub grades: Option<String>,
+    /// Card style, only `classic` for now.
+    pub style: Option<String>,
+    /// Draws roll-tier markers under each substat.
+    pub rolls: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RollsQuery {
+    pub uid: i32,
+    pub cid: u32,
+    pub lang: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RelicRolls {
+    pub name: String,
+    pub set_name: String,
+    pub rarity: u8,
+    pub level: u8,
+    /// Rolls of every substat, including the initial ones.
+    pub rolls: u32,
+    /// Sum of the rolls as a percentage of the same number of high rolls.
+    pub efficiency: f64,
+    pub substats: Vec<SubstatRolls>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubstatRolls {
+    pub name: String,
+    pub field: String,
+    pub display: String,
+    pub rolls: u8,
+    /// Steps above the low roll, summed over every roll.
+    pub steps: u8,
+    /// 1 (low) to 3 (high) per roll, spread evenly from `steps`.
+    pub tiers: Vec<u8>,
+    pub efficiency: f64,
 }
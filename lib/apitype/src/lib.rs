@@ -1,2 +1,3 @@
 pub mod genshin;
 pub mod hsr;
+pub mod zzz;
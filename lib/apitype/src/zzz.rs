@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProfileQuery {
+    pub uid: i32,
+    pub lang: Option<String>,
+    pub image_format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct User {
+    pub level: u32,
+    pub uid: i32,
+    pub name: String,
+    pub description: String,
+    pub from_cache: bool,
+    pub characters: Vec<UserCharacter>,
+    pub lastupdate: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UserCharacter {
+    pub level: u32,
+    pub mindscape: u32,
+    pub profession: String,
+    pub element: Option<String>,
+    pub name: String,
+    pub icon: String,
+    pub id: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerateQuery {
+    pub uid: i32,
+    pub lang: Option<String>,
+    pub image_format: Option<String>,
+    pub cid: u32,
+    pub counter: Option<String>,
+}
@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use filetime::FileTime;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::{
+    fs::{create_dir_all, read_dir, remove_file, try_exists, write, File},
+    io::AsyncReadExt,
+};
+
+use crate::{CacheBackend, EvictionPolicy, Usage};
+
+pub struct FsCache {
+    root: PathBuf,
+}
+
+struct Entry {
+    path: PathBuf,
+    key: String,
+    len: u64,
+    modified: SystemTime,
+    accessed: SystemTime,
+}
+
+impl FsCache {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    async fn entries(&self) -> std::io::Result<Vec<Entry>> {
+        let mut entries = Vec::new();
+        if let Ok(false) = try_exists(&self.root).await {
+            return Ok(entries);
+        }
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut rd = match read_dir(&dir).await {
+                Ok(rd) => rd,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            while let Some(e) = rd.next_entry().await? {
+                // entries may be removed by a concurrent sweep while listing
+                let meta = match e.metadata().await {
+                    Ok(meta) => meta,
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+                let path = e.path();
+                if meta.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let key = path
+                    .strip_prefix(&self.root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let modified = meta.modified()?;
+                entries.push(Entry {
+                    key,
+                    len: meta.len(),
+                    modified,
+                    accessed: meta.accessed().unwrap_or(modified),
+                    path,
+                });
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Removes a swept entry, returning whether it is gone. An entry that was
+/// already removed counts as gone; other failures are logged and skipped.
+async fn remove(path: &Path) -> bool {
+    match remove_file(path).await {
+        Ok(()) => true,
+        Err(e) if e.kind() == ErrorKind::NotFound => true,
+        Err(e) => {
+            log::warn!("failed to remove cache entry {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for FsCache {
+    async fn set(&self, key: String, value: &[u8], time: SystemTime) -> std::io::Result<()> {
+        let path = self.root.join(key);
+        if let Some(p) = path.parent() {
+            if let Ok(false) = try_exists(p).await {
+                create_dir_all(p).await?;
+            }
+        }
+        write(&path, value).await?;
+        filetime::set_file_times(
+            &path,
+            FileTime::from_system_time(SystemTime::now()),
+            FileTime::from_system_time(time),
+        )?;
+        Ok(())
+    }
+    async fn get(&self, key: &str) -> std::io::Result<(Vec<u8>, SystemTime)> {
+        let path = self.root.join(key);
+        let mut f = File::open(&path).await?;
+        let meta = f.metadata().await?;
+        let modtime = meta.modified()?;
+        let mut buf = Vec::with_capacity(meta.len() as usize);
+        f.read_to_end(&mut buf).await?;
+        // the mtime is the fetch time, so only the atime is bumped for LRU
+        let _ = filetime::set_file_atime(&path, FileTime::from_system_time(SystemTime::now()));
+        Ok((buf, modtime))
+    }
+    async fn usage(&self) -> std::io::Result<Usage> {
+        let entries = self.entries().await?;
+        Ok(Usage {
+            entries: entries.len() as u64,
+            bytes: entries.iter().map(|e| e.len).sum(),
+        })
+    }
+    async fn sweep(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        let now = SystemTime::now();
+        let mut removed = Usage::default();
+        let mut kept = Vec::new();
+        for e in self.entries().await? {
+            if !policy.is_expired(&e.key, e.modified, now) {
+                kept.push(e);
+            } else if remove(&e.path).await {
+                removed.entries += 1;
+                removed.bytes += e.len;
+            }
+        }
+        if let Some(max_bytes) = policy.max_bytes {
+            let mut total: u64 = kept.iter().map(|e| e.len).sum();
+            kept.sort_by_key(|e| e.accessed);
+            for e in kept {
+                if total <= max_bytes {
+                    break;
+                }
+                if !remove(&e.path).await {
+                    continue;
+                }
+                total -= e.len;
+                removed.entries += 1;
+                removed.bytes += e.len;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[tokio::test]
+    async fn keeps_time() {
+        let dir = temp_dir("fs-time");
+        keeps_fetch_time(FsCache::new(&dir)).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn sweeps_expired() {
+        let dir = temp_dir("fs-expired");
+        sweep_drops_expired(FsCache::new(&dir)).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn sweeps_to_max_bytes() {
+        let dir = temp_dir("fs-bytes");
+        sweep_trims_least_recently_used(FsCache::new(&dir)).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn sweep_skips_entries_removed_meanwhile() {
+        let cache = FsCache::new(temp_dir("fs-removed"));
+        cache
+            .set("ui/a".to_string(), b"a", SystemTime::now())
+            .await
+            .unwrap();
+        assert!(remove(&cache.root().join("ui/a")).await);
+        assert!(remove(&cache.root().join("ui/a")).await);
+        assert!(remove(&cache.root().join("ui/missing/b")).await);
+        let removed = cache.sweep(&EvictionPolicy::default()).await.unwrap();
+        assert_eq!(removed.entries, 0);
+        let _ = std::fs::remove_dir_all(cache.root());
+    }
+
+    #[tokio::test]
+    async fn missing_root_is_empty() {
+        let cache = FsCache::new(temp_dir("fs-missing"));
+        assert_eq!(cache.usage().await.unwrap().entries, 0);
+    }
+}
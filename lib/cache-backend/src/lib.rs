@@ -0,0 +1,238 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+
+mod fs;
+mod memory;
+mod ratelimit;
+mod single_flight;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use fs::FsCache;
+pub use memory::MemoryCache;
+pub use ratelimit::{parse_retry_after, RateLimit, RateLimiter, RetryPolicy};
+pub use single_flight::SingleFlight;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteCache;
+
+/// Storage for cached upstream responses.
+///
+/// Every entry carries the time it was fetched, which callers compare against
+/// their own TTL the same way the filesystem cache uses the file mtime.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn set(&self, key: String, value: &[u8], time: SystemTime) -> std::io::Result<()>;
+    async fn get(&self, key: &str) -> std::io::Result<(Vec<u8>, SystemTime)>;
+    /// Number of entries and total bytes currently stored.
+    async fn usage(&self) -> std::io::Result<Usage>;
+    /// Removes entries that violate `policy`, returning what was removed.
+    async fn sweep(&self, policy: &EvictionPolicy) -> std::io::Result<Usage>;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Usage {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CacheStats {
+    pub entries: u64,
+    pub bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Limits enforced by [`CacheBackend::sweep`].
+///
+/// The namespace of a key is its first path segment (`ui`, `user`, ...).
+/// Entries older than their namespace's max age are dropped first, then the
+/// least recently accessed entries are dropped until the cache fits in `max_bytes`.
+#[derive(Clone, Debug, Default)]
+pub struct EvictionPolicy {
+    pub max_bytes: Option<u64>,
+    pub max_age: HashMap<String, Duration>,
+    pub default_max_age: Option<Duration>,
+}
+
+impl EvictionPolicy {
+    pub fn max_age_for(&self, key: &str) -> Option<Duration> {
+        self.max_age
+            .get(namespace(key))
+            .copied()
+            .or(self.default_max_age)
+    }
+    pub(crate) fn is_expired(&self, key: &str, time: SystemTime, now: SystemTime) -> bool {
+        match self.max_age_for(key) {
+            Some(age) => now.duration_since(time).unwrap_or_default() > age,
+            None => false,
+        }
+    }
+}
+
+pub fn namespace(key: &str) -> &str {
+    key.split('/').next().unwrap_or(key)
+}
+
+/// A cache backend that also counts hits and misses.
+pub struct Cache {
+    backend: Box<dyn CacheBackend>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Cache {
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+    pub async fn set(&self, key: String, value: &[u8], time: SystemTime) -> std::io::Result<()> {
+        self.backend.set(key, value, time).await
+    }
+    pub async fn get(&self, key: impl AsRef<str>) -> std::io::Result<(Vec<u8>, SystemTime)> {
+        let res = self.backend.get(key.as_ref()).await;
+        let counter = if res.is_ok() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        res
+    }
+    pub async fn stats(&self) -> std::io::Result<CacheStats> {
+        let usage = self.backend.usage().await?;
+        Ok(CacheStats {
+            entries: usage.entries,
+            bytes: usage.bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        })
+    }
+    pub async fn sweep(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        self.backend.sweep(policy).await
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    /// A directory under the system temp dir that no other test uses.
+    pub(crate) fn temp_dir(name: &str) -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "cache-backend-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    fn within_a_second(a: SystemTime, b: SystemTime) -> bool {
+        a.duration_since(b)
+            .or_else(|_| b.duration_since(a))
+            .unwrap()
+            < Duration::from_secs(1)
+    }
+
+    /// Entries keep the time they were fetched, which callers compare to their TTL.
+    pub(crate) async fn keeps_fetch_time(backend: impl CacheBackend) {
+        let fetched = SystemTime::now() - HOUR;
+        backend
+            .set("user/1/en".to_string(), b"profile", fetched)
+            .await
+            .unwrap();
+        let (value, time) = backend.get("user/1/en").await.unwrap();
+        assert_eq!(value, b"profile");
+        assert!(within_a_second(time, fetched));
+        assert_eq!(
+            backend.get("user/2/en").await.unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    pub(crate) async fn sweep_drops_expired(backend: impl CacheBackend) {
+        let now = SystemTime::now();
+        backend
+            .set("user/old".to_string(), b"12345", now - 2 * HOUR)
+            .await
+            .unwrap();
+        backend
+            .set("user/new".to_string(), b"12345", now)
+            .await
+            .unwrap();
+        backend
+            .set("ui/old".to_string(), b"12345", now - 2 * HOUR)
+            .await
+            .unwrap();
+        let mut policy = EvictionPolicy::default();
+        policy.max_age.insert("user".to_string(), HOUR);
+        let removed = backend.sweep(&policy).await.unwrap();
+        assert_eq!((removed.entries, removed.bytes), (1, 5));
+        assert!(backend.get("user/old").await.is_err());
+        assert!(backend.get("user/new").await.is_ok());
+        assert!(backend.get("ui/old").await.is_ok());
+        assert_eq!(backend.usage().await.unwrap().entries, 2);
+    }
+
+    pub(crate) async fn sweep_trims_least_recently_used(backend: impl CacheBackend) {
+        for key in ["ui/a", "ui/b", "ui/c"] {
+            backend
+                .set(key.to_string(), &[0; 10], SystemTime::now())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        backend.get("ui/a").await.unwrap();
+        let policy = EvictionPolicy {
+            max_bytes: Some(20),
+            ..Default::default()
+        };
+        let removed = backend.sweep(&policy).await.unwrap();
+        assert_eq!((removed.entries, removed.bytes), (1, 10));
+        assert!(backend.get("ui/b").await.is_err());
+        assert!(backend.get("ui/a").await.is_ok());
+        assert!(backend.get("ui/c").await.is_ok());
+    }
+
+    #[test]
+    fn policy_uses_namespace_max_age() {
+        let mut policy = EvictionPolicy {
+            default_max_age: Some(HOUR),
+            ..Default::default()
+        };
+        policy.max_age.insert("user".to_string(), 2 * HOUR);
+        let now = SystemTime::now();
+        assert_eq!(policy.max_age_for("user/1/en"), Some(2 * HOUR));
+        assert_eq!(policy.max_age_for("ui/icon.png"), Some(HOUR));
+        assert!(!policy.is_expired("user/1", now - HOUR - HOUR / 2, now));
+        assert!(policy.is_expired("ui/icon.png", now - HOUR - HOUR / 2, now));
+        assert!(!EvictionPolicy::default().is_expired("ui/a", SystemTime::UNIX_EPOCH, now));
+    }
+
+    #[tokio::test]
+    async fn cache_counts_hits_and_misses() {
+        let cache = Cache::new(MemoryCache::new(4));
+        cache
+            .set("ui/a".to_string(), b"a", SystemTime::now())
+            .await
+            .unwrap();
+        cache.get("ui/a").await.unwrap();
+        cache.get("ui/b").await.unwrap_err();
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 1));
+    }
+}
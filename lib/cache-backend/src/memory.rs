@@ -0,0 +1,109 @@
+use std::{io::ErrorKind, num::NonZeroUsize, sync::Mutex, time::SystemTime};
+
+use async_trait::async_trait;
+use lru::LruCache;
+
+use crate::{CacheBackend, EvictionPolicy, Usage};
+
+pub struct MemoryCache {
+    inner: Mutex<LruCache<String, (Vec<u8>, SystemTime)>>,
+}
+
+impl MemoryCache {
+    /// Keeps at most `capacity` entries, evicting the least recently used one.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn set(&self, key: String, value: &[u8], time: SystemTime) -> std::io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.put(key, (value.to_vec(), time));
+        Ok(())
+    }
+    async fn get(&self, key: &str) -> std::io::Result<(Vec<u8>, SystemTime)> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .get(key)
+            .cloned()
+            .ok_or_else(|| std::io::Error::from(ErrorKind::NotFound))
+    }
+    async fn usage(&self) -> std::io::Result<Usage> {
+        let inner = self.inner.lock().unwrap();
+        Ok(Usage {
+            entries: inner.len() as u64,
+            bytes: inner.iter().map(|(_, (v, _))| v.len() as u64).sum(),
+        })
+    }
+    async fn sweep(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        let now = SystemTime::now();
+        let mut inner = self.inner.lock().unwrap();
+        let mut removed = Usage::default();
+        let expired: Vec<String> = inner
+            .iter()
+            .filter(|(k, (_, time))| policy.is_expired(k, *time, now))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in expired {
+            if let Some((v, _)) = inner.pop(&key) {
+                removed.entries += 1;
+                removed.bytes += v.len() as u64;
+            }
+        }
+        if let Some(max_bytes) = policy.max_bytes {
+            let mut total: u64 = inner.iter().map(|(_, (v, _))| v.len() as u64).sum();
+            while total > max_bytes {
+                match inner.pop_lru() {
+                    Some((_, (v, _))) => {
+                        total -= v.len() as u64;
+                        removed.entries += 1;
+                        removed.bytes += v.len() as u64;
+                    }
+                    None => break,
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[tokio::test]
+    async fn keeps_time() {
+        keeps_fetch_time(MemoryCache::new(8)).await;
+    }
+
+    #[tokio::test]
+    async fn sweeps_expired() {
+        sweep_drops_expired(MemoryCache::new(8)).await;
+    }
+
+    #[tokio::test]
+    async fn sweeps_to_max_bytes() {
+        sweep_trims_least_recently_used(MemoryCache::new(8)).await;
+    }
+
+    #[tokio::test]
+    async fn evicts_beyond_capacity() {
+        let cache = MemoryCache::new(1);
+        cache
+            .set("a".to_string(), b"a", SystemTime::now())
+            .await
+            .unwrap();
+        cache
+            .set("b".to_string(), b"b", SystemTime::now())
+            .await
+            .unwrap();
+        assert!(cache.get("a").await.is_err());
+        assert!(cache.get("b").await.is_ok());
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Token bucket settings applied to each upstream host separately.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    /// Requests that may be sent back to back before the limiter kicks in.
+    pub burst: u32,
+    /// Tokens refilled per second.
+    pub per_second: f64,
+    /// How long a request may wait for a token before it is refused.
+    pub max_wait: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 10,
+            per_second: 2.0,
+            max_wait: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RateLimit {
+    /// A loose limit for static files such as images, which a single card
+    /// fetches dozens of at once.
+    pub fn assets() -> Self {
+        Self {
+            burst: 100,
+            per_second: 50.0,
+            max_wait: Duration::from_secs(10),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Takes a token for `host`, waiting for one if it becomes available within
+    /// `max_wait`. Returns `false` when the request should not be sent.
+    pub async fn acquire(&self, host: &str) -> bool {
+        let wait = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let now = Instant::now();
+            let burst = self.limit.burst as f64;
+            let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+                tokens: burst,
+                updated: now,
+            });
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.limit.per_second).min(burst);
+            bucket.updated = now;
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                Duration::ZERO
+            } else {
+                let secs = (1.0 - bucket.tokens) / self.limit.per_second;
+                if !secs.is_finite() || secs > self.limit.max_wait.as_secs_f64() {
+                    return false;
+                }
+                // reserve the token so later callers queue up behind this one
+                bucket.tokens -= 1.0;
+                Duration::from_secs_f64(secs)
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        true
+    }
+}
+
+/// Retry behaviour for rate limited (429) and failed (5xx) upstream responses.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Upper bound for a single delay. A `Retry-After` longer than this is not waited for.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+    pub fn is_retryable(status: u16) -> bool {
+        status == 429 || (500..600).contains(&status)
+    }
+    /// Delay before retry number `attempt` (starting at 0), or `None` to give up.
+    ///
+    /// `retry_after` from the upstream wins over the exponential backoff, which
+    /// is jittered between half and the full delay.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        if let Some(retry_after) = retry_after {
+            return (retry_after <= self.max_delay).then_some(retry_after);
+        }
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_delay);
+        Some(delay.mul_f64(0.5 + rand::random::<f64>() * 0.5))
+    }
+}
+
+/// Parses a `Retry-After` header, given either as seconds or as an HTTP date.
+///
+/// Dates in the past resolve to a zero delay.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(burst: u32, per_second: f64, max_wait: Duration) -> RateLimiter {
+        RateLimiter::new(RateLimit {
+            burst,
+            per_second,
+            max_wait,
+        })
+    }
+
+    #[tokio::test]
+    async fn limiter_refuses_once_burst_is_spent() {
+        let limiter = limit(2, 0.001, Duration::from_millis(10));
+        assert!(limiter.acquire("a").await);
+        assert!(limiter.acquire("a").await);
+        assert!(!limiter.acquire("a").await);
+    }
+
+    #[tokio::test]
+    async fn asset_limit_covers_a_cold_card() {
+        let limiter = RateLimiter::new(RateLimit::assets());
+        for _ in 0..40 {
+            assert!(limiter.acquire("cdn").await);
+        }
+    }
+
+    #[tokio::test]
+    async fn limiter_tracks_hosts_separately() {
+        let limiter = limit(1, 0.001, Duration::from_millis(10));
+        assert!(limiter.acquire("a").await);
+        assert!(!limiter.acquire("a").await);
+        assert!(limiter.acquire("b").await);
+    }
+
+    #[tokio::test]
+    async fn limiter_waits_for_a_refill_within_max_wait() {
+        let limiter = limit(1, 100.0, Duration::from_secs(1));
+        assert!(limiter.acquire("a").await);
+        let start = Instant::now();
+        assert!(limiter.acquire("a").await);
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+
+    #[tokio::test]
+    async fn limiter_refuses_without_refill() {
+        let limiter = limit(1, 0.0, Duration::from_secs(1));
+        assert!(limiter.acquire("a").await);
+        assert!(!limiter.acquire("a").await);
+    }
+
+    #[test]
+    fn delay_gives_up_after_max_retries() {
+        let retry = RetryPolicy::default();
+        assert!(retry.delay(retry.max_retries - 1, None).is_some());
+        assert_eq!(retry.delay(retry.max_retries, None), None);
+        assert_eq!(RetryPolicy::none().delay(0, None), None);
+    }
+
+    #[test]
+    fn delay_backs_off_with_jitter() {
+        let retry = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        for (attempt, full) in [(0, 100), (1, 200), (2, 400), (5, 1000)] {
+            let delay = retry.delay(attempt, None).unwrap();
+            let full = Duration::from_millis(full);
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn delay_prefers_retry_after() {
+        let retry = RetryPolicy::default();
+        let after = Duration::from_secs(3);
+        assert_eq!(retry.delay(0, Some(after)), Some(after));
+        assert_eq!(retry.delay(0, Some(retry.max_delay * 2)), None);
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(RetryPolicy::is_retryable(429));
+        assert!(RetryPolicy::is_retryable(503));
+        assert!(!RetryPolicy::is_retryable(404));
+        assert!(!RetryPolicy::is_retryable(424));
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_dates() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 5 "), Some(Duration::from_secs(5)));
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        let delay = parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
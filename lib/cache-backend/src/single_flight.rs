@@ -0,0 +1,93 @@
+use std::{collections::HashMap, future::Future, hash::Hash, sync::Arc, sync::Mutex};
+
+use tokio::sync::OnceCell;
+
+/// Deduplicates concurrent calls for the same key so that only one of them
+/// runs the work and every caller receives a clone of its result.
+pub struct SingleFlight<K, V> {
+    inflight: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K, V> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self {
+            inflight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> SingleFlight<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub async fn run<F, Fut>(&self, key: K, f: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let cell = self
+            .inflight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        let value = cell.get_or_init(f).await.clone();
+        let mut inflight = self.inflight.lock().unwrap();
+        if let Some(current) = inflight.get(&key) {
+            if Arc::ptr_eq(current, &cell) {
+                inflight.remove(&key);
+            }
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Counts its calls and yields once so concurrent callers overlap.
+    async fn work(calls: &AtomicUsize, value: u32) -> u32 {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        value
+    }
+
+    #[tokio::test]
+    async fn coalesces_concurrent_calls() {
+        let flight = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+        let (a, b, c) = tokio::join!(
+            flight.run(1, || work(&calls, 10)),
+            flight.run(1, || work(&calls, 20)),
+            flight.run(1, || work(&calls, 30)),
+        );
+        assert_eq!((a, b, c), (10, 10, 10));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn runs_each_key_separately() {
+        let flight = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+        let (a, b) = tokio::join!(
+            flight.run(1, || work(&calls, 10)),
+            flight.run(2, || work(&calls, 20)),
+        );
+        assert_eq!((a, b), (10, 20));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn forgets_finished_calls() {
+        let flight = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+        assert_eq!(flight.run(1, || work(&calls, 10)).await, 10);
+        assert_eq!(flight.run(1, || work(&calls, 20)).await, 20);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(flight.inflight.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    io::ErrorKind,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Error as SqlxError, Row, SqlitePool,
+};
+
+use crate::{CacheBackend, EvictionPolicy, Usage};
+
+pub struct SqliteCache {
+    pool: SqlitePool,
+}
+
+impl SqliteCache {
+    /// Opens (creating if needed) the database at `url`, e.g. `sqlite://cache.db`.
+    pub async fn connect(url: &str) -> Result<Self, SqlxError> {
+        let options: SqliteConnectOptions = url.parse()?;
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options.create_if_missing(true))
+            .await?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS cache (key TEXT PRIMARY KEY, value BLOB NOT NULL, time INTEGER NOT NULL, accessed INTEGER NOT NULL)",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(Self { pool })
+    }
+    async fn delete(&self, key: &str) -> std::io::Result<()> {
+        sqlx::query("DELETE FROM cache WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(to_io)?;
+        Ok(())
+    }
+}
+
+fn to_io(e: SqlxError) -> std::io::Error {
+    match e {
+        SqlxError::RowNotFound => std::io::Error::from(ErrorKind::NotFound),
+        e => std::io::Error::other(e),
+    }
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis as u64)
+}
+
+#[async_trait]
+impl CacheBackend for SqliteCache {
+    async fn set(&self, key: String, value: &[u8], time: SystemTime) -> std::io::Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO cache (key, value, time, accessed) VALUES (?, ?, ?, ?)",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(to_millis(time))
+        .bind(to_millis(SystemTime::now()))
+        .execute(&self.pool)
+        .await
+        .map_err(to_io)?;
+        Ok(())
+    }
+    async fn get(&self, key: &str) -> std::io::Result<(Vec<u8>, SystemTime)> {
+        let row = sqlx::query("UPDATE cache SET accessed = ? WHERE key = ? RETURNING value, time")
+            .bind(to_millis(SystemTime::now()))
+            .bind(key)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(to_io)?;
+        let value: Vec<u8> = row.try_get("value").map_err(to_io)?;
+        let millis: i64 = row.try_get("time").map_err(to_io)?;
+        Ok((value, from_millis(millis)))
+    }
+    async fn usage(&self) -> std::io::Result<Usage> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS entries, COALESCE(SUM(LENGTH(value)), 0) AS bytes FROM cache",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(to_io)?;
+        let entries: i64 = row.try_get("entries").map_err(to_io)?;
+        let bytes: i64 = row.try_get("bytes").map_err(to_io)?;
+        Ok(Usage {
+            entries: entries as u64,
+            bytes: bytes as u64,
+        })
+    }
+    async fn sweep(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        let now = SystemTime::now();
+        let rows =
+            sqlx::query("SELECT key, LENGTH(value) AS len, time FROM cache ORDER BY accessed ASC")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(to_io)?;
+        let mut removed = Usage::default();
+        let mut kept = Vec::new();
+        for row in rows {
+            let key: String = row.try_get("key").map_err(to_io)?;
+            let len: i64 = row.try_get("len").map_err(to_io)?;
+            let time: i64 = row.try_get("time").map_err(to_io)?;
+            if policy.is_expired(&key, from_millis(time), now) {
+                self.delete(&key).await?;
+                removed.entries += 1;
+                removed.bytes += len as u64;
+            } else {
+                kept.push((key, len as u64));
+            }
+        }
+        if let Some(max_bytes) = policy.max_bytes {
+            let mut total: u64 = kept.iter().map(|(_, len)| len).sum();
+            for (key, len) in kept {
+                if total <= max_bytes {
+                    break;
+                }
+                self.delete(&key).await?;
+                total -= len;
+                removed.entries += 1;
+                removed.bytes += len;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    async fn cache(dir: &std::path::Path) -> SqliteCache {
+        std::fs::create_dir_all(dir).unwrap();
+        let url = format!("sqlite://{}", dir.join("cache.db").display());
+        SqliteCache::connect(&url).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn keeps_time() {
+        let dir = temp_dir("sqlite-time");
+        keeps_fetch_time(cache(&dir).await).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn sweeps_expired() {
+        let dir = temp_dir("sqlite-expired");
+        sweep_drops_expired(cache(&dir).await).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn sweeps_to_max_bytes() {
+        let dir = temp_dir("sqlite-bytes");
+        sweep_trims_least_recently_used(cache(&dir).await).await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,20 +1,43 @@
-use std::time::SystemTime;
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
 
 use crate::{
-    cache::Cache,
+    cache::{default_cache, Cache, CacheBackend, CacheStats, EvictionPolicy, SingleFlight, Usage},
+    error::EnkaError,
+    profile::{
+        is_valid_hash, is_valid_username, parse_builds, parse_hoyos, parse_profile, Build,
+        EnkaProfile, Hoyo,
+    },
+    raw::{parse, RawUser},
     store::Store,
+    updater::{StoreFiles, DEFAULT_STORE_URL},
     user::{ApiRawUser, ApiUser},
 };
 use image::{io::Reader as ImageReader, DynamicImage};
-use reqwest::{Client, Error as ReqwestError};
+use reqwest::{header::RETRY_AFTER, Client, Url};
+
+use cache_backend::{parse_retry_after, RateLimiter};
+pub use cache_backend::{RateLimit, RetryPolicy};
 
 pub struct Api {
     pub client: Client,
-    pub store: Store,
+    store: RwLock<Arc<Store>>,
+    store_dir: Option<PathBuf>,
+    store_url: String,
     pub cache: Cache,
+    enka_url: String,
+    inflight: SingleFlight<i32, Result<ApiRawUser, EnkaError>>,
+    limiter: RateLimiter,
+    asset_limiter: RateLimiter,
+    retry: RetryPolicy,
 }
 
+pub const DEFAULT_ENKA_URL: &str = "https://enka.network";
 const USER_AGENT: &str = "EnkaApi/0.1.0 (+https://artifacter.neody.land/)";
+const PROFILE_TTL: Duration = Duration::from_secs(5 * 60);
 
 impl Default for Api {
     fn default() -> Self {
@@ -24,57 +47,119 @@ impl Default for Api {
 
 impl Api {
     pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .user_agent(USER_AGENT)
-                .build()
-                .expect("Failed to build reqwest client"),
-            store: Store::new(),
-            cache: Cache::new(),
-        }
+        Self::builder().build()
+    }
+    /// Builds an `Api` that stores its cache in `backend` instead of the default directory.
+    pub fn with_cache(backend: impl CacheBackend + 'static) -> Self {
+        Self::builder().cache(backend).build()
+    }
+    pub fn builder() -> ApiBuilder {
+        ApiBuilder::default()
+    }
+    pub async fn cache_stats(&self) -> std::io::Result<CacheStats> {
+        self.cache.stats().await
     }
-    async fn request(&self, uri: &str) -> Result<Vec<u8>, ReqwestError> {
-        log::info!("requesting {}", uri);
-        let request = self.client.get(uri);
-        let body = request.send().await?;
-        let body = body.error_for_status()?;
-        let body = body.bytes().await?;
-        Ok(body.to_vec())
+    /// Evicts cache entries violating `policy`, returning what was removed.
+    pub async fn sweep_cache(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        self.cache.sweep(policy).await
     }
-    async fn assets(&self, path: impl AsRef<str>) -> Result<Vec<u8>, String> {
+    /// Sends a GET through `limiter`, retrying per the retry policy.
+    async fn request(&self, limiter: &RateLimiter, uri: &str) -> Result<Vec<u8>, EnkaError> {
+        let host = Url::parse(uri)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+        let mut attempt = 0;
+        loop {
+            if !limiter.acquire(&host).await {
+                log::warn!("rate limit reached for {}", host);
+                return Err(EnkaError::RateLimited { retry_after: None });
+            }
+            log::info!("requesting {}", uri);
+            let request = self.client.get(uri);
+            let body = request.send().await?;
+            let status = body.status();
+            if status.is_client_error() || status.is_server_error() {
+                let retry_after = body
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(parse_retry_after);
+                if RetryPolicy::is_retryable(status.as_u16()) {
+                    if let Some(delay) = self.retry.delay(attempt, retry_after) {
+                        log::warn!("{} returned {}, retrying in {:?}", uri, status, delay);
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                }
+                return Err(EnkaError::from_status(
+                    status.as_u16(),
+                    retry_after.map(|d| d.as_secs()),
+                ));
+            }
+            let body = body.bytes().await?;
+            return Ok(body.to_vec());
+        }
+    }
+    async fn assets(&self, path: impl AsRef<str>) -> Result<Vec<u8>, EnkaError> {
         if let Ok((buf, _time)) = self.cache.get(&path).await {
             return Ok(buf.to_vec());
         }
-        let url = format!("https://enka.network/{}", path.as_ref());
-        let body = match self.request(&url).await {
-            Ok(body) => body,
-            Err(e) => return Err(format!("{}", e)),
-        };
+        let url = format!("{}/{}", self.enka_url, path.as_ref());
+        let body = self
+            .request(&self.asset_limiter, &url)
+            .await
+            .map_err(|e| match e {
+                EnkaError::NotFound => EnkaError::AssetMissing(path.as_ref().to_string()),
+                e => e,
+            })?;
         self.cache
             .set(path.as_ref().to_string(), &body, SystemTime::now())
             .await
             .ok();
         Ok(body)
     }
-    pub async fn ui_image(&self, path: impl AsRef<str>) -> Result<DynamicImage, String> {
+    pub async fn ui_image(&self, path: impl AsRef<str>) -> Result<DynamicImage, EnkaError> {
         let url = format!("ui/{}.png", path.as_ref());
         let body = self.assets(&url).await?;
-        let reader = ImageReader::new(std::io::Cursor::new(body));
-        let reader = match reader.with_guessed_format() {
-            Ok(img) => img,
-            Err(e) => return Err(format!("{}", e)),
-        };
-        match reader.decode() {
-            Ok(img) => Ok(img),
-            Err(e) => Err(format!("{}", e)),
-        }
+        let reader = ImageReader::new(std::io::Cursor::new(body))
+            .with_guessed_format()
+            .map_err(|e| EnkaError::Decode(format!("{}", e)))?;
+        Ok(reader.decode()?)
     }
-    pub fn get_store(&self) -> &Store {
-        &self.store
+    /// The current store. Lookups in progress keep the store they started with,
+    /// which is dropped once the last of them finishes after a swap.
+    pub fn get_store(&self) -> Arc<Store> {
+        self.store.read().unwrap().clone()
     }
-    async fn fetch_user(&self, uid: i32) -> Result<ApiRawUser, Option<ReqwestError>> {
+    /// Replaces the store used for every following lookup.
+    pub fn swap_store(&self, store: Store) {
+        *self.store.write().unwrap() = Arc::new(store);
+    }
+    /// Re-reads the store directory, keeping the current store if it cannot be loaded.
+    pub fn reload_store(&self) -> Result<(), EnkaError> {
+        let dir = self
+            .store_dir
+            .as_ref()
+            .ok_or_else(|| EnkaError::Decode(String::from("no store directory configured")))?;
+        self.swap_store(Store::from_dir(dir)?);
+        Ok(())
+    }
+    /// Downloads the latest store files, saves them to the store directory if one is
+    /// configured and swaps them in.
+    pub async fn update_store(&self) -> Result<(), EnkaError> {
+        let files = StoreFiles::download(&self.client, &self.store_url).await?;
+        let store = files.parse()?;
+        if let Some(dir) = &self.store_dir {
+            files.write_to(dir).await?;
+        }
+        self.swap_store(store);
+        Ok(())
+    }
+    async fn fetch_user(&self, uid: i32) -> Result<ApiRawUser, EnkaError> {
         let contents = self
-            .request(&format!("https://enka.network/api/uid/{}", uid))
+            .request(&self.limiter, &format!("{}/api/uid/{}", self.enka_url, uid))
             .await?;
         let lastupdate = SystemTime::now();
         Ok(ApiRawUser::from_raw(contents, uid, lastupdate))
@@ -85,11 +170,31 @@ impl Api {
             .set(format!("user/{}", data.uid()), data.contents(), now)
             .await
     }
+    /// Resolves a raw `/api/uid` payload, such as a saved response, without
+    /// requesting the user from Enka.Network. Only missing UI assets are fetched.
+    pub fn resolve_raw(&self, contents: Vec<u8>) -> Result<ApiUser, EnkaError> {
+        let raw: RawUser = parse("user", &contents)?;
+        let uid = raw.uid.unwrap_or(0) as i32;
+        ApiRawUser::from_raw(contents, uid, SystemTime::now()).resolve(self)
+    }
+    /// Fetches and caches the user, sharing one upstream request among concurrent callers.
+    async fn fetch_and_cache(&self, uid: i32) -> Result<ApiRawUser, EnkaError> {
+        self.inflight
+            .run(uid, || async {
+                let raw = self.fetch_user(uid).await?;
+                let _ = self.set_cache(&raw).await;
+                Ok(raw)
+            })
+            .await
+    }
     async fn find_cache(&self, uid: i32) -> Option<ApiRawUser> {
         let (buf, modtime) = self.cache.get(format!("user/{}", uid)).await.ok()?;
         Some(ApiRawUser::from_raw(buf.to_vec(), uid, modtime))
     }
-    pub async fn simple(&self, uid: i32) -> Result<(ApiUser, bool), String> {
+    pub async fn simple(&self, uid: i32) -> Result<(ApiUser, bool), EnkaError> {
+        if !(100_000_000..=i32::MAX).contains(&uid) {
+            return Err(EnkaError::InvalidUid);
+        }
         match self.find_cache(uid).await {
             Some(cache) => {
                 let data = cache.resolve(self)?;
@@ -98,46 +203,247 @@ impl Api {
                         Some(new) => Ok((new, false)),
                         None => Ok((data, false)),
                     },
-                    Err(e) => {
-                        if e.contains("424") {
-                            Ok((data, true))
-                        } else {
-                            Err(e)
-                        }
-                    }
+                    Err(EnkaError::Maintenance | EnkaError::RateLimited { .. }) => Ok((data, true)),
+                    Err(e) => Err(e),
                 }
             }
             None => {
-                let userdata = self.fetch_user(uid).await;
-                match userdata {
-                    Ok(userdata) => {
-                        let _ = self.set_cache(&userdata).await;
-                        Ok((userdata.resolve(self)?, false))
-                    }
-                    Err(e) => Err(match e {
-                        Some(e) => format!("{}", e),
-                        None => String::from("unknown error"),
-                    }),
-                }
+                let userdata = self.fetch_and_cache(uid).await?;
+                Ok((userdata.resolve(self)?, false))
             }
         }
     }
-    async fn reload(&self, data: &ApiUser) -> Result<Option<ApiUser>, String> {
+    /// Fetches `api/profile/{path}`, reusing cached copies for `PROFILE_TTL`
+    /// and falling back to a stale one when Enka.Network is unavailable.
+    async fn profile_json(&self, path: &str) -> Result<Vec<u8>, EnkaError> {
+        let key = format!("profile/{}", path);
+        let cached = self.cache.get(&key).await.ok();
+        if let Some((buf, modtime)) = &cached {
+            if modtime.elapsed().unwrap_or_default() < PROFILE_TTL {
+                return Ok(buf.clone());
+            }
+        }
+        let url = format!("{}/api/profile/{}/?format=json", self.enka_url, path);
+        match self.request(&self.limiter, &url).await {
+            Ok(data) => {
+                let _ = self.cache.set(key, &data, SystemTime::now()).await;
+                Ok(data)
+            }
+            Err(
+                e @ (EnkaError::Maintenance
+                | EnkaError::RateLimited { .. }
+                | EnkaError::UpstreamHttp(_)
+                | EnkaError::Transport(_)),
+            ) => match cached {
+                Some((buf, _)) => {
+                    log::warn!(
+                        "failed to refresh profile/{}, using stale copy: {}",
+                        path,
+                        e
+                    );
+                    Ok(buf)
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// Fetches an Enka.Network account by its username.
+    pub async fn profile(&self, username: &str) -> Result<EnkaProfile, EnkaError> {
+        if !is_valid_username(username) {
+            return Err(EnkaError::InvalidUsername);
+        }
+        parse_profile(&self.profile_json(username).await?)
+    }
+    /// Lists the Genshin accounts linked to an Enka.Network account.
+    pub async fn hoyos(&self, username: &str) -> Result<Vec<Hoyo>, EnkaError> {
+        if !is_valid_username(username) {
+            return Err(EnkaError::InvalidUsername);
+        }
+        parse_hoyos(&self.profile_json(&format!("{}/hoyos", username)).await?)
+    }
+    /// Lists the builds saved for one linked account, ordered by character.
+    pub async fn builds(&self, username: &str, hash: &str) -> Result<Vec<Build>, EnkaError> {
+        if !is_valid_username(username) {
+            return Err(EnkaError::InvalidUsername);
+        }
+        if !is_valid_hash(hash) {
+            return Err(EnkaError::HoyoNotFound);
+        }
+        let path = format!("{}/hoyos/{}/builds", username, hash);
+        let builds = match self.profile_json(&path).await {
+            Ok(data) => parse_builds(self, &data)?,
+            Err(EnkaError::NotFound) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        // An unknown hash looks like a hoyo without builds, or like an unknown user.
+        if builds.is_empty() && !self.hoyos(username).await?.iter().any(|h| h.hash == hash) {
+            return Err(EnkaError::HoyoNotFound);
+        }
+        Ok(builds)
+    }
+    async fn reload(&self, data: &ApiUser) -> Result<Option<ApiUser>, EnkaError> {
         let lastupdate = SystemTime::now();
         if data.reload_time() >= lastupdate {
             Ok(None)
         } else {
-            let raw = self.fetch_user(data.uid()).await;
-            match raw {
-                Ok(raw) => {
-                    let _ = self.set_cache(&raw).await;
-                    Ok(Some(raw.resolve(self)?))
-                }
-                Err(e) => Err(match e {
-                    Some(e) => format!("{}", e),
-                    None => String::from("unknown error"),
-                }),
-            }
+            let raw = self.fetch_and_cache(data.uid()).await?;
+            Ok(Some(raw.resolve(self)?))
         }
     }
 }
+
+pub struct ApiBuilder {
+    cache: Option<Cache>,
+    enka_url: String,
+    rate_limit: RateLimit,
+    asset_rate_limit: RateLimit,
+    retry: RetryPolicy,
+    store_dir: Option<PathBuf>,
+    store_url: String,
+}
+
+impl Default for ApiBuilder {
+    fn default() -> Self {
+        Self {
+            cache: None,
+            enka_url: DEFAULT_ENKA_URL.to_string(),
+            rate_limit: RateLimit::default(),
+            asset_rate_limit: RateLimit::assets(),
+            retry: RetryPolicy::default(),
+            store_dir: None,
+            store_url: DEFAULT_STORE_URL.to_string(),
+        }
+    }
+}
+
+impl ApiBuilder {
+    pub fn cache(mut self, backend: impl CacheBackend + 'static) -> Self {
+        self.cache = Some(Cache::new(backend));
+        self
+    }
+    /// Base URL of Enka.Network, without a trailing slash. Defaults to `DEFAULT_ENKA_URL`.
+    pub fn enka_url(mut self, url: impl Into<String>) -> Self {
+        self.enka_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// Token bucket applied to each upstream host for user and profile requests.
+    /// Cached data is served as stale when it refuses.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = limit;
+        self
+    }
+    /// Token bucket for UI images. Defaults to `RateLimit::assets()`, since one
+    /// card fetches a dozen of them.
+    pub fn asset_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.asset_rate_limit = limit;
+        self
+    }
+    /// Retries for 429 and 5xx responses. Use `RetryPolicy::none()` to disable.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    /// Directory the store is loaded from and updates are saved to. The bundled
+    /// store is used when it is unset or cannot be loaded.
+    pub fn store_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.store_dir = Some(dir.into());
+        self
+    }
+    /// Base URL of the Enka API-docs store, without a trailing slash. Defaults to `DEFAULT_STORE_URL`.
+    pub fn store_url(mut self, url: impl Into<String>) -> Self {
+        self.store_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    pub fn build(self) -> Api {
+        let store = match &self.store_dir {
+            Some(dir) => Store::from_dir(dir).unwrap_or_else(|e| {
+                log::warn!(
+                    "failed to load store from {}, using the bundled one: {}",
+                    dir.display(),
+                    e
+                );
+                Store::new()
+            }),
+            None => Store::new(),
+        };
+        Api {
+            client: Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("Failed to build reqwest client"),
+            store: RwLock::new(Arc::new(store)),
+            store_dir: self.store_dir,
+            store_url: self.store_url,
+            cache: self.cache.unwrap_or_else(default_cache),
+            enka_url: self.enka_url,
+            inflight: SingleFlight::new(),
+            limiter: RateLimiter::new(self.rate_limit),
+            asset_limiter: RateLimiter::new(self.asset_rate_limit),
+            retry: self.retry,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::cache::MemoryCache;
+
+    /// An `Api` whose cache already holds fresh copies of the given profile pages.
+    async fn seeded_api(pages: &[(&str, &str)]) -> Api {
+        let api = Api::builder().cache(MemoryCache::new(8)).build();
+        for (path, body) in pages {
+            api.cache
+                .set(
+                    format!("profile/{}", path),
+                    body.as_bytes(),
+                    SystemTime::now(),
+                )
+                .await
+                .unwrap();
+        }
+        api
+    }
+
+    #[tokio::test]
+    async fn unknown_hashes_are_missing_hoyos() {
+        let api = seeded_api(&[
+            ("someone/hoyos/abc123/builds", "{}"),
+            ("someone/hoyos", "{}"),
+        ])
+        .await;
+        assert!(matches!(
+            api.builds("someone", "abc123").await,
+            Err(EnkaError::HoyoNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_assets_name_the_asset() {
+        // Answers the one request with a 404, like Enka.Network for an unknown icon.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+        });
+        let api = Api::builder()
+            .cache(MemoryCache::new(8))
+            .enka_url(format!("http://{}", addr))
+            .build();
+        assert!(matches!(
+            api.ui_image("UI_NameCardPic_0_P").await,
+            Err(EnkaError::AssetMissing(path)) if path == "ui/UI_NameCardPic_0_P.png"
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use crate::{api::Api, character::Reliquary};
+
+/// Piece counts at which an artifact set grants a bonus.
+pub const SET_BONUS_PIECES: [u8; 2] = [2, 4];
+
+/// Equipped artifacts sharing one set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ArtifactSet {
+    pub set_name: u32,
+    pub count: u8,
+}
+
+impl ArtifactSet {
+    /// Piece bonuses unlocked by the equipped pieces, e.g. `[2, 4]`.
+    pub fn active_bonuses(&self) -> Vec<u8> {
+        SET_BONUS_PIECES
+            .into_iter()
+            .filter(|n| self.count >= *n)
+            .collect()
+    }
+    pub fn name(&self, api: &Api, language: impl AsRef<str>) -> Option<String> {
+        api.get_store()
+            .locale(language, self.set_name.to_string())
+            .map(str::to_string)
+    }
+}
+
+/// Groups `reliquarys` by set and keeps the sets with at least one active bonus,
+/// most pieces first.
+pub fn active_sets(reliquarys: &[Reliquary]) -> Vec<ArtifactSet> {
+    sets_of(reliquarys.iter().map(Reliquary::set_name_hash))
+}
+
+fn sets_of(set_names: impl IntoIterator<Item = u32>) -> Vec<ArtifactSet> {
+    let mut counts = HashMap::<u32, u8>::new();
+    for set_name in set_names {
+        *counts.entry(set_name).or_default() += 1;
+    }
+    let mut sets = counts
+        .into_iter()
+        .map(|(set_name, count)| ArtifactSet { set_name, count })
+        .filter(|s| !s.active_bonuses().is_empty())
+        .collect::<Vec<_>>();
+    sets.sort_by(|a, b| b.count.cmp(&a.count).then(a.set_name.cmp(&b.set_name)));
+    sets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(set_name: u32, count: u8) -> ArtifactSet {
+        ArtifactSet { set_name, count }
+    }
+
+    #[test]
+    fn bonuses_follow_piece_count() {
+        assert!(set(1, 1).active_bonuses().is_empty());
+        assert_eq!(set(1, 3).active_bonuses(), [2]);
+        assert_eq!(set(1, 5).active_bonuses(), [2, 4]);
+    }
+
+    #[test]
+    fn keeps_sets_with_a_bonus_most_pieces_first() {
+        assert_eq!(sets_of([7, 3, 7, 7, 3]), [set(7, 3), set(3, 2)]);
+        assert_eq!(sets_of([7, 7, 7, 7, 3]), [set(7, 4)]);
+        // ties are ordered by set so the card does not flicker between requests
+        assert_eq!(sets_of([9, 2, 9, 2, 5]), [set(2, 2), set(9, 2)]);
+    }
+
+    #[test]
+    fn no_sets_without_a_pair() {
+        assert!(sets_of([1, 2, 3, 4, 5]).is_empty());
+        assert!(sets_of([]).is_empty());
+    }
+}
@@ -0,0 +1,17 @@
+//! Downloads the latest store files into a directory (`dynamic-assets` by default).
+
+use enka_api::updater::{StoreFiles, DEFAULT_STORE_URL};
+use reqwest::Client;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "dynamic-assets".to_string());
+    let url = std::env::var("ENKA_STORE_URL").unwrap_or_else(|_| DEFAULT_STORE_URL.to_string());
+    let files = StoreFiles::download(&Client::new(), url.trim_end_matches('/'))
+        .await
+        .expect("failed to download store");
+    files.write_to(&dir).await.expect("failed to write store");
+    println!("updated store in {}", dir);
+}
@@ -1,10 +1,12 @@
 use std::{borrow::Cow, collections::HashMap, fmt::Display, str::FromStr};
 
 use image::DynamicImage;
-use serde_json::{Number, Value};
 
+use crate::artifact_set::{active_sets, ArtifactSet};
 use crate::element::Element;
-use crate::util::{ascension_level_map, get_or_null};
+use crate::error::EnkaError;
+use crate::raw::{RawAvatarInfo, RawEquip, RawReliquary, RawStat, RawWeapon};
+use crate::util::ascension_level_map;
 use crate::{api::Api, fight_prop::FightProp};
 
 #[derive(Clone, Debug)]
@@ -32,9 +34,10 @@ impl Character {
     pub fn talents(&self) -> &Vec<CharacterTalent> {
         &self.talents
     }
-    pub fn name<'a>(&self, api: &'a Api, language: impl AsRef<str>) -> Result<&'a str, String> {
+    pub fn name(&self, api: &Api, language: impl AsRef<str>) -> Result<String, String> {
         api.get_store()
             .locale(language, &format!("{}", self.name))
+            .map(str::to_string)
             .ok_or_else(|| String::from("no locale data"))
     }
     pub fn costumes(&self) -> Vec<&Costume> {
@@ -92,6 +95,10 @@ impl Character {
     pub fn reliquarys(&self) -> &Vec<Reliquary> {
         &self.reliquarys
     }
+    /// Artifact sets with an active 2 or 4-piece bonus.
+    pub fn artifact_sets(&self) -> Vec<ArtifactSet> {
+        active_sets(&self.reliquarys)
+    }
     pub fn weapon(&self) -> &Weapon {
         &self.weapon
     }
@@ -101,10 +108,16 @@ impl Character {
 }
 #[derive(Hash, Copy, Clone, Eq, PartialEq, Debug)]
 pub struct CharacterId(pub u32);
-pub(crate) fn parse_character(api: &Api, player_character: &Value) -> Option<Character> {
-    let avatar_id = player_character.get("avatarId")?.as_u64()? as u32;
+/// Resolves one showcased character against the store. Errors name the avatar
+/// and the field that could not be resolved.
+pub(crate) fn parse_character(
+    api: &Api,
+    player_character: &RawAvatarInfo,
+) -> Result<Character, EnkaError> {
+    let avatar_id = player_character.avatar_id;
+    let error = |what: String| EnkaError::Decode(format!("avatar {}: {}", avatar_id, what));
     let character_id_str = if avatar_id == 10000005 || avatar_id == 10000007 {
-        let depot_id = match player_character.get("skillDepotId") {
+        let depot_id = match player_character.skill_depot_id {
             Some(depot_id) => depot_id.to_string(),
             None => format!("{}01", avatar_id.to_string().get(7..7).unwrap()),
         };
@@ -113,146 +126,85 @@ pub(crate) fn parse_character(api: &Api, player_character: &Value) -> Option<Cha
         format!("{}", avatar_id)
     };
     let store = api.get_store();
-    let characters = store.characters.get(character_id_str)?.as_object()?;
-    let mut talents = vec![];
-    if let Some(consts) = characters.get("Consts") {
-        if let Some(consts) = consts.as_array() {
-            let talent_count = {
-                match player_character.get("talentIdList") {
-                    Some(consts) => match consts.as_array() {
-                        Some(arr) => arr.len(),
-                        None => 0,
-                    },
-                    None => 0,
-                }
+    let characters = store.characters.get(&character_id_str).ok_or_else(|| {
+        error(format!(
+            "character {} is not in the store",
+            character_id_str
+        ))
+    })?;
+    let talent_count = player_character.talent_id_list.len();
+    let talents = characters
+        .consts
+        .iter()
+        .enumerate()
+        .map(|(index, image)| CharacterTalent {
+            image: image.clone(),
+            unlock: index < talent_count,
+        })
+        .collect();
+    let skills = characters
+        .skill_order
+        .iter()
+        .filter_map(|id| {
+            let level = *player_character.skill_level_map.get(id)?;
+            let extra_level = characters
+                .proud_map
+                .get(id)
+                .and_then(|proud| player_character.proud_skill_extra_level_map.get(proud))
+                .copied()
+                .unwrap_or(0);
+            Some(CharacterSkill {
+                extra_level,
+                id: *id,
+                level,
+                image: characters.skills.get(id).cloned(),
+            })
+        })
+        .collect();
+    let current_costume = player_character.costume_id.map(|id| id as u64);
+    let costumes = characters
+        .costumes
+        .iter()
+        .map(|(id, costume)| {
+            let c = Costume {
+                id: *id,
+                useing: player_character.costume_id == Some(*id),
+                art: Some(costume.art.clone()),
+                icon: Some(costume.icon.clone()),
+                side_icon: Some(costume.side_icon.clone()),
             };
-            for (index, name) in consts.iter().enumerate() {
-                let talent = CharacterTalent {
-                    image: name.as_str()?.to_owned(),
-                    unlock: index < talent_count,
-                };
-                talents.push(talent);
-            }
-        }
-    }
-    fn find(map: &serde_json::Map<String, Value>, key: &str) -> Option<String> {
-        Some(String::from(map.get(key)?.as_str()?))
-    }
-    let mut skills: Vec<CharacterSkill> = vec![];
-    {
-        let extra_map = get_or_null(player_character, "proudSkillExtraLevelMap");
-        let skill_level_map = get_or_null(player_character, "skillLevelMap");
-        let skill_level_map = skill_level_map.as_object();
-        if let Some(skill_level_map) = skill_level_map {
-            let skill_image = characters.get("Skills")?;
-            let mut skill_map = HashMap::with_capacity(4);
-            for (id, level) in skill_level_map {
-                let image = match skill_image.get(id) {
-                    Some(v) => v.as_str().map(|str| str.to_owned()),
-                    None => None,
-                };
-                let extra_level = match characters.get("ProudMap") {
-                    Some(map) => match map.get(id) {
-                        Some(id) => match extra_map.get(id.to_string()) {
-                            Some(v) => match v.as_u64() {
-                                Some(v) => v as u8,
-                                None => 0,
-                            },
-                            None => 0,
-                        },
-                        None => 0,
-                    },
-                    None => 0,
-                };
-                let id = u64::from_str(id).ok()? as u32;
-                let s = CharacterSkill {
-                    extra_level,
-                    id,
-                    level: level.as_u64()? as u8,
-                    image,
-                };
-                skill_map.insert(id, s);
-            }
-            let skill_order = characters.get("SkillOrder");
-            if let Some(skill_order) = skill_order {
-                if let Some(skill_order) = skill_order.as_array() {
-                    for skill_id in skill_order {
-                        if let Some(skill_id) = skill_id.as_u64() {
-                            if let Some(s) = skill_map.remove(&(skill_id as u32)) {
-                                skills.push(s);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    let current_costume = match player_character.get("costumeId") {
-        Some(json) => json.as_u64(),
-        None => None,
-    };
-    let mut costumes = HashMap::new();
-    if let Some(costumes_list) = characters.get("Costumes") {
-        if let Some(costumes_list) = costumes_list.as_object() {
-            for (id, costume_json) in costumes_list {
-                fn load_costume(id: &str, useing: bool, json: &Value) -> Option<Costume> {
-                    fn str_or(json: &Value, key: &str) -> Option<String> {
-                        Some(json.get(key)?.as_str()?.to_owned())
-                    }
-                    Some(Costume {
-                        id: u32::from_str(id).ok()?,
-                        useing,
-                        art: str_or(json, "art"),
-                        icon: str_or(json, "icon"),
-                        side_icon: str_or(json, "sideIconName"),
-                    })
-                }
-                let is_useing = match current_costume {
-                    Some(costume_id) => costume_id.to_string().as_str() == id,
-                    None => false,
-                };
-                if let Some(c) = load_costume(id, is_useing, costume_json) {
-                    costumes.insert(c.id, c);
-                }
-            }
-        }
-    }
-    let friendship = match player_character.get("fetterInfo") {
-        Some(fetter_info) => match fetter_info.get("expLevel") {
-            Some(exp) => match exp.as_u64() {
-                Some(friendship) => friendship as u8,
-                None => 1u8,
-            },
-            None => 1u8,
-        },
-        None => 1u8,
-    };
-    let prop_map = player_character.get("propMap")?;
-    fn prop(prop_map: &Value, key: &str) -> Option<u64> {
-        let s = prop_map.get(key)?.get("val")?.as_str()?;
-        u64::from_str(s).ok()
-    }
-    let (weapon, reliquarys) = parse_equip_list(player_character.get("equipList")?.as_array()?);
-    Some(Character {
+            (*id, c)
+        })
+        .collect();
+    let friendship = player_character
+        .fetter_info
+        .as_ref()
+        .map(|f| f.exp_level)
+        .unwrap_or(1);
+    let (weapon, reliquarys) = parse_equip_list(&player_character.equip_list).map_err(error)?;
+    let element = Element::from_str(&characters.element)
+        .map_err(|_| error(format!("unknown element {}", characters.element)))?;
+    let props = &player_character.prop_map;
+    let images = characters.images.as_ref();
+    Ok(Character {
         id: CharacterId(avatar_id),
-        fight_prop: FightProp::from_json(player_character.get("fightPropMap")?),
-        element: Element::from_str(characters.get("Element")?.as_str()?).ok()?,
-        name: characters.get("NameTextMapHash")?.as_u64()? as u32,
+        fight_prop: FightProp::from_map(&player_character.fight_prop_map),
+        element,
+        name: characters.name_text_map_hash,
         talents,
         skills,
         reliquarys,
-        weapon: weapon?,
+        weapon: weapon.ok_or_else(|| error("equipList has no weapon".to_string()))?,
         costumes,
         current_costume,
-        gachaslice: find(characters, "namegachaslice").or(find(characters, "filename_gachaslice")),
-        gachasplash: find(characters, "namegachasplash")
-            .or(find(characters, "filename_gachasplash")),
-        icon: find(characters, "nameicon").or(find(characters, "filename_icon")),
-        iconcard: find(characters, "nameiconcard").or(find(characters, "filename_iconcard")),
+        gachaslice: images.and_then(|i| i.filename_gacha_slice.clone()),
+        gachasplash: images.and_then(|i| i.filename_gacha_splash.clone()),
+        icon: images.map(|i| i.filename_icon.clone()),
+        iconcard: images.map(|i| i.filename_icon_card.clone()),
         friendship,
-        xp: prop(prop_map, "1001").unwrap_or(0) as u32,
-        ascension: prop(prop_map, "1002").unwrap_or(0) as u8,
-        level: prop(prop_map, "4001").unwrap_or(0) as u8,
+        xp: props.xp.value() as u32,
+        ascension: props.ascension.value() as u8,
+        level: props.level.val as u8,
     })
 }
 //命の星座
@@ -268,9 +220,9 @@ impl CharacterTalent {
     pub fn path(&self) -> &String {
         &self.image
     }
-    pub async fn image(&self, api: &Api) -> Result<DynamicImage, String> {
+    pub async fn image(&self, api: &Api) -> Result<DynamicImage, EnkaError> {
         if self.image.is_empty() {
-            return Err(String::from("None"));
+            return Err(EnkaError::AssetMissing("constellation icon".to_string()));
         }
         api.ui_image(&self.image).await
     }
@@ -311,10 +263,13 @@ impl CharacterSkill {
     pub fn extra_level(&self) -> u8 {
         self.extra_level
     }
-    pub async fn image(&self, api: &Api) -> Result<DynamicImage, String> {
+    pub async fn image(&self, api: &Api) -> Result<DynamicImage, EnkaError> {
         match &self.image {
             Some(path) => api.ui_image(path).await,
-            None => Err("no image".to_string()),
+            None => Err(EnkaError::AssetMissing(format!(
+                "icon of skill {}",
+                self.id
+            ))),
         }
     }
 }
@@ -378,8 +333,10 @@ impl Stats {
             _ => Cow::Borrowed(""),
         }
     }
-    pub fn name<'a>(&self, api: &'a Api, language: impl AsRef<str>) -> Option<&'a str> {
-        api.get_store().locale(language, self.id())
+    pub fn name(&self, api: &Api, language: impl AsRef<str>) -> Option<String> {
+        api.get_store()
+            .locale(language, self.id())
+            .map(str::to_string)
     }
 }
 #[derive(Copy, Clone, Debug)]
@@ -430,19 +387,23 @@ impl Reliquary {
     pub fn set_name_hash(&self) -> u32 {
         self.set_name
     }
-    pub fn set_name<'a>(&self, api: &'a Api, language: impl AsRef<str>) -> Option<&'a str> {
-        api.get_store().locale(language, self.set_name.to_string())
+    pub fn set_name(&self, api: &Api, language: impl AsRef<str>) -> Option<String> {
+        api.get_store()
+            .locale(language, self.set_name.to_string())
+            .map(str::to_string)
     }
     pub fn name_hash(&self) -> u64 {
         self.name
     }
-    pub fn name<'a>(&self, api: &'a Api, language: impl AsRef<str>) -> Option<&'a str> {
-        api.get_store().locale(language, self.name.to_string())
+    pub fn name(&self, api: &Api, language: impl AsRef<str>) -> Option<String> {
+        api.get_store()
+            .locale(language, self.name.to_string())
+            .map(str::to_string)
     }
     pub fn name_icon(&self) -> &String {
         &self.icon
     }
-    pub async fn image_icon(&self, api: &Api) -> Result<DynamicImage, String> {
+    pub async fn image_icon(&self, api: &Api) -> Result<DynamicImage, EnkaError> {
         api.ui_image(self.name_icon()).await
     }
 }
@@ -462,102 +423,142 @@ impl Weapon {
     pub fn name_hash(&self) -> u64 {
         self.name
     }
-    pub fn name<'a>(&self, api: &'a Api, language: impl AsRef<str>) -> Option<&'a str> {
-        api.get_store().locale(language, self.name.to_string())
+    pub fn name(&self, api: &Api, language: impl AsRef<str>) -> Option<String> {
+        api.get_store()
+            .locale(language, self.name.to_string())
+            .map(str::to_string)
     }
     pub fn name_icon(&self) -> &String {
         &self.icon
     }
-    pub async fn image_icon(&self, api: &Api) -> Result<DynamicImage, String> {
+    pub async fn image_icon(&self, api: &Api) -> Result<DynamicImage, EnkaError> {
         api.ui_image(self.name_icon()).await
     }
     pub fn ascension_level(&self) -> u8 {
         ascension_level_map(self.ascension)
     }
 }
-fn parse_equip_list(list: &Vec<Value>) -> (Option<Weapon>, Vec<Reliquary>) {
+fn parse_equip_list(list: &[RawEquip]) -> Result<(Option<Weapon>, Vec<Reliquary>), String> {
     let mut weapon = None;
     let mut reliquarys = vec![];
-    for entry in list {
-        if entry.get("reliquary").is_some() {
-            if let Some(r) = parse_equip_reliquary(entry) {
-                reliquarys.push(r);
-            }
-        } else if entry.get("weapon").is_some() {
-            if let Some(w) = parse_equip_weapon(entry) {
-                weapon = Some(w);
-            }
+    for (index, entry) in list.iter().enumerate() {
+        if let Some(reliquary) = &entry.reliquary {
+            let r = parse_equip_reliquary(entry, reliquary).map_err(|field| {
+                format!("equipList[{}] (item {}): {}", index, entry.item_id, field)
+            })?;
+            reliquarys.push(r);
+        } else if let Some(w) = &entry.weapon {
+            weapon = Some(parse_equip_weapon(entry, w));
         }
     }
-    (weapon, reliquarys)
+    Ok((weapon, reliquarys))
 }
 
-fn to_u64(v: &Value) -> Option<u64> {
-    v.as_u64()
-        .or_else(|| v.as_str().and_then(|s| u64::from_str(s).ok()))
-}
-
-fn parse_equip_reliquary(entry: &Value) -> Option<Reliquary> {
-    let id = entry.get("itemId")?.as_u64()? as u32;
-    let reliquary = entry.get("reliquary")?;
-    let flat = entry.get("flat")?;
-    let reliquary_substats = flat.get("reliquarySubstats");
+/// Errors with the path of the missing field.
+fn parse_equip_reliquary(
+    entry: &RawEquip,
+    reliquary: &RawReliquary,
+) -> Result<Reliquary, &'static str> {
+    let flat = &entry.flat;
     let mut sub_stats = [None, None, None, None];
-    if let Some(reliquary_substats) = reliquary_substats {
-        if let Some(reliquary_substats) = reliquary_substats.as_array() {
-            for (index, v) in reliquary_substats.iter().enumerate() {
-                sub_stats[index] = parse_reliquary_stat(v, "appendPropId");
-            }
-        }
-    }
-    Some(Reliquary {
-        id,
-        position: ReliquaryType::parse(flat.get("equipType")?.as_str()?).ok()?,
-        icon: flat.get("icon")?.as_str()?.to_owned(),
-        name: to_u64(flat.get("nameTextMapHash")?)?,
-        set_name: to_u64(flat.get("setNameTextMapHash")?)? as u32,
-        rarity: flat.get("rankLevel")?.as_u64()? as u8,
-        level: (reliquary.get("level")?.as_u64()? - 1) as u8,
-        main_stats: parse_reliquary_stat(flat.get("reliquaryMainstat")?, "mainPropId")?,
+    for (index, v) in flat.reliquary_substats.iter().take(4).enumerate() {
+        sub_stats[index] = Some(parse_reliquary_stat(v));
+    }
+    let position = flat
+        .equip_type
+        .as_ref()
+        .and_then(|t| ReliquaryType::parse(t).ok())
+        .ok_or("flat.equipType is missing or unknown")?;
+    Ok(Reliquary {
+        id: entry.item_id,
+        position,
+        icon: flat.icon.clone(),
+        name: flat.name_text_map_hash,
+        set_name: flat
+            .set_name_text_map_hash
+            .ok_or("flat.setNameTextMapHash is missing")? as u32,
+        rarity: flat.rank_level,
+        level: reliquary.level.saturating_sub(1),
+        main_stats: parse_reliquary_stat(
+            flat.reliquary_mainstat
+                .as_ref()
+                .ok_or("flat.reliquaryMainstat is missing")?,
+        ),
         sub_stats,
     })
 }
-fn parse_reliquary_stat(v: &Value, id_key: &str) -> Option<StatsValue> {
-    Some(StatsValue(
-        Stats::parse(v.get(id_key)?.as_str()?),
-        v.get("statValue")?.as_f64()?,
-    ))
+fn parse_reliquary_stat(v: &RawStat) -> StatsValue {
+    StatsValue(Stats::parse(&v.append_prop_id), v.stat_value)
 }
-fn parse_equip_weapon(entry: &Value) -> Option<Weapon> {
-    let id = entry.get("itemId")?.as_u64()? as u32;
-    let weapon = entry.get("weapon")?;
-    let flat = entry.get("flat")?;
-    let stats_list = flat.get("weaponStats")?.as_array()?;
+fn parse_equip_weapon(entry: &RawEquip, weapon: &RawWeapon) -> Weapon {
+    let flat = &entry.flat;
     let mut base_attack = 0;
     let mut stats = None;
-    for v in stats_list {
-        let prop_name = v.get("appendPropId")?.as_str()?;
-        let value = v.get("statValue")?.as_f64()?;
-        match prop_name {
-            "FIGHT_PROP_BASE_ATTACK" => base_attack = value as i32,
-            a => stats = Some(StatsValue(Stats::parse(a), value)),
+    for v in &flat.weapon_stats {
+        match v.append_prop_id.as_str() {
+            "FIGHT_PROP_BASE_ATTACK" => base_attack = v.stat_value as i32,
+            a => stats = Some(StatsValue(Stats::parse(a), v.stat_value)),
         }
     }
-    Some(Weapon {
-        id,
-        level: weapon
-            .get("level")
-            .unwrap_or(&Value::Number(Number::from_str("0").ok()?))
-            .as_u64()? as u8,
-        ascension: weapon
-            .get("promoteLevel")
-            .unwrap_or(&Value::Number(Number::from_str("0").ok()?))
-            .as_u64()? as u8,
-        refinement: weapon.get("affixMap")?.get(format!("1{}", id))?.as_u64()? as u8,
-        rarity: flat.get("rankLevel")?.as_u64()? as u8,
-        icon: flat.get("icon")?.as_str()?.to_owned(),
-        name: to_u64(flat.get("nameTextMapHash")?)?,
+    Weapon {
+        id: entry.item_id,
+        level: weapon.level,
+        ascension: weapon.promote_level,
+        refinement: weapon
+            .affix_map
+            .get(&format!("1{}", entry.item_id))
+            .copied()
+            .unwrap_or(0),
+        rarity: flat.rank_level,
+        icon: flat.icon.clone(),
+        name: flat.name_text_map_hash,
         base_attack,
         stats,
-    })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equip(json: &str) -> RawEquip {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const FLOWER: &str = r#"{
+        "itemId": 81234,
+        "reliquary": {"level": 21},
+        "flat": {
+            "nameTextMapHash": "1",
+            "setNameTextMapHash": "2",
+            "rankLevel": 5,
+            "icon": "UI_RelicIcon_15031_4",
+            "equipType": "EQUIP_BRACER",
+            "reliquaryMainstat": {"mainPropId": "FIGHT_PROP_HP", "statValue": 4780},
+            "reliquarySubstats": [{"appendPropId": "FIGHT_PROP_CRITICAL", "statValue": 3.9}]
+        }
+    }"#;
+
+    #[test]
+    fn parses_artifacts() {
+        let (weapon, reliquarys) = parse_equip_list(&[equip(FLOWER)]).unwrap();
+        assert!(weapon.is_none());
+        assert_eq!(reliquarys[0].position, ReliquaryType::Flower);
+        assert_eq!(reliquarys[0].level, 20);
+        assert_eq!(reliquarys[0].set_name_hash(), 2);
+    }
+
+    #[test]
+    fn names_the_missing_artifact_field() {
+        let fields = [
+            ("\"equipType\": \"EQUIP_BRACER\",", "flat.equipType"),
+            ("\"setNameTextMapHash\": \"2\",", "flat.setNameTextMapHash"),
+        ];
+        for (field, path) in fields {
+            let broken = equip(&FLOWER.replace(field, ""));
+            let err = parse_equip_list(&[equip(FLOWER), broken]).unwrap_err();
+            assert!(err.starts_with("equipList[1] (item 81234)"), "{}", err);
+            assert!(err.contains(path), "{}", err);
+        }
+    }
 }
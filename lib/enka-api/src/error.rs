@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum EnkaError {
+    /// The UID is malformed or was rejected by Enka.Network (400).
+    InvalidUid,
+    /// The Enka.Network username is malformed.
+    InvalidUsername,
+    /// No player or profile exists with the given UID or name (404).
+    NotFound,
+    /// The profile exists but has no linked account with the given hash.
+    HoyoNotFound,
+    /// Enka.Network is rate limiting us (429). `retry_after` is in seconds.
+    RateLimited {
+        retry_after: Option<u64>,
+    },
+    /// The game server is under maintenance or Enka.Network cannot reach it (424).
+    Maintenance,
+    /// Any other upstream status.
+    UpstreamHttp(u16),
+    /// No response was received at all.
+    Transport(String),
+    /// An image or store file does not exist upstream, or the data has no path for it.
+    AssetMissing(String),
+    /// The upstream payload or an asset could not be decoded.
+    Decode(String),
+    Cache(std::io::Error),
+}
+
+impl EnkaError {
+    pub(crate) fn from_status(status: u16, retry_after: Option<u64>) -> Self {
+        match status {
+            400 => Self::InvalidUid,
+            404 => Self::NotFound,
+            424 => Self::Maintenance,
+            429 => Self::RateLimited { retry_after },
+            s => Self::UpstreamHttp(s),
+        }
+    }
+}
+
+impl Display for EnkaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidUid => write!(f, "invalid uid"),
+            Self::InvalidUsername => write!(f, "invalid username"),
+            Self::NotFound => write!(f, "player not found"),
+            Self::HoyoNotFound => write!(f, "linked account not found"),
+            Self::RateLimited {
+                retry_after: Some(s),
+            } => write!(f, "rate limited, retry after {}s", s),
+            Self::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            Self::Maintenance => write!(f, "game server is under maintenance"),
+            Self::UpstreamHttp(s) => write!(f, "upstream returned status {}", s),
+            Self::Transport(e) => write!(f, "upstream is unreachable: {}", e),
+            Self::AssetMissing(a) => write!(f, "asset not found: {}", a),
+            Self::Decode(e) => write!(f, "failed to decode: {}", e),
+            Self::Cache(e) => write!(f, "cache error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EnkaError {}
+
+impl Clone for EnkaError {
+    fn clone(&self) -> Self {
+        match self {
+            Self::InvalidUid => Self::InvalidUid,
+            Self::InvalidUsername => Self::InvalidUsername,
+            Self::NotFound => Self::NotFound,
+            Self::HoyoNotFound => Self::HoyoNotFound,
+            Self::RateLimited { retry_after } => Self::RateLimited {
+                retry_after: *retry_after,
+            },
+            Self::Maintenance => Self::Maintenance,
+            Self::UpstreamHttp(s) => Self::UpstreamHttp(*s),
+            Self::Transport(e) => Self::Transport(e.clone()),
+            Self::AssetMissing(a) => Self::AssetMissing(a.clone()),
+            Self::Decode(e) => Self::Decode(e.clone()),
+            // io::Error is not Clone, so keep its kind and message
+            Self::Cache(e) => Self::Cache(std::io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
+impl From<std::io::Error> for EnkaError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Cache(e)
+    }
+}
+
+impl From<reqwest::Error> for EnkaError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(s) => Self::from_status(s.as_u16(), None),
+            None if e.is_decode() => Self::Decode(format!("{}", e)),
+            None => Self::Transport(format!("{}", e)),
+        }
+    }
+}
+
+impl From<image::ImageError> for EnkaError {
+    fn from(e: image::ImageError) -> Self {
+        Self::Decode(format!("{}", e))
+    }
+}
+
+impl From<serde_json::Error> for EnkaError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Decode(format!("{}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_map_to_variants() {
+        assert!(matches!(
+            EnkaError::from_status(400, None),
+            EnkaError::InvalidUid
+        ));
+        assert!(matches!(
+            EnkaError::from_status(404, None),
+            EnkaError::NotFound
+        ));
+        assert!(matches!(
+            EnkaError::from_status(424, None),
+            EnkaError::Maintenance
+        ));
+        assert!(matches!(
+            EnkaError::from_status(429, Some(3)),
+            EnkaError::RateLimited {
+                retry_after: Some(3)
+            }
+        ));
+        assert!(matches!(
+            EnkaError::from_status(503, None),
+            EnkaError::UpstreamHttp(503)
+        ));
+    }
+}
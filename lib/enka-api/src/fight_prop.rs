@@ -1,7 +1,5 @@
 use std::collections::HashMap;
 
-use serde_json::Value;
-
 use crate::{element::Element, store::Store};
 
 #[derive(Clone, Debug)]
@@ -80,16 +78,14 @@ pub struct ElementalReactionCritical {
     pub hyperbloom_rate: f64,
     pub hyperbloom_damage: f64,
 }
-fn value_or(json: &Value, key: impl AsRef<str>, def: impl Into<f64>) -> f64 {
+/// `fightPropMap` only lists the props a character has, so absent ones take `def`.
+fn value_or(json: &HashMap<String, f64>, key: impl AsRef<str>, def: impl Into<f64>) -> f64 {
     match json.get(key.as_ref()) {
-        Some(value) => match value.as_f64() {
-            Some(f) => f,
-            None => def.into(),
-        },
+        Some(value) => *value,
         None => def.into(),
     }
 }
-fn parse_erc(json: &Value) -> ElementalReactionCritical {
+fn parse_erc(json: &HashMap<String, f64>) -> ElementalReactionCritical {
     ElementalReactionCritical {
         rate: value_or(json, "3025", 0),
         damage: value_or(json, "3026", 0),
@@ -140,7 +136,7 @@ impl FightProp {
             _ => Element::None,
         }
     }
-    pub fn from_json(json: &Value) -> FightProp {
+    pub fn from_map(json: &HashMap<String, f64>) -> FightProp {
         let mut fp = Self {
             base_hp: value_or(json, "1", 0),
             hp: value_or(json, "2", 0),
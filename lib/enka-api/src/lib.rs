@@ -1,12 +1,17 @@
 pub mod api;
+pub mod artifact_set;
 pub mod cache;
 pub mod character;
 pub mod constants;
 pub mod element;
+pub mod error;
 pub mod fight_prop;
 pub mod icon;
 pub mod playerinfo;
+pub mod profile;
+pub mod raw;
 pub mod store;
+pub mod updater;
 pub mod user;
 pub mod util;
 
@@ -1,7 +1,6 @@
 use image::DynamicImage;
-use serde_json::Value;
 
-use crate::{api::Api, character::CharacterId, util::get_or_null};
+use crate::{api::Api, character::CharacterId, error::EnkaError, raw::RawPlayerInfo};
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct PlayerInfo {
     nickname: String,
@@ -17,45 +16,33 @@ pub struct PlayerInfo {
     name_card_list: Vec<NameCard>,
 }
 
-impl From<&Value> for PlayerInfo {
-    fn from(v: &Value) -> Self {
-        let mut avatar_info_list = vec![];
-        if let Some(arr) = get_or_null(v, "showAvatarInfoList").as_array() {
-            for item in arr {
-                if let Some(id) = get_or_null(item, "avatarId").as_u64() {
-                    avatar_info_list.push(CharacterId(id as u32));
-                }
-            }
-        }
-        let mut name_card_list = vec![];
-        if let Some(arr) = get_or_null(v, "showNameCardIdList").as_array() {
-            for item in arr {
-                if let Some(id) = item.as_u64() {
-                    name_card_list.push(NameCard(id as u32));
-                }
-            }
-        }
+impl From<&RawPlayerInfo> for PlayerInfo {
+    fn from(v: &RawPlayerInfo) -> Self {
         Self {
-            nickname: get_or_null(v, "nickname").as_str().unwrap_or("").to_owned(),
-            signature: get_or_null(v, "signature")
-                .as_str()
-                .unwrap_or("")
-                .to_owned(),
-            level: get_or_null(v, "level").as_u64().unwrap_or(0) as u8,
-            world_level: get_or_null(v, "worldLevel").as_u64().unwrap_or(0) as u8,
-            achievement: get_or_null(v, "finishAchievementNum").as_u64().unwrap_or(0) as u32,
-            name_card: get_or_null(v, "nameCardId")
-                .as_u64()
-                .map(|f| NameCard(f as u32)),
-            tower_floor_index: get_or_null(v, "towerFloorIndex").as_u64().unwrap_or(0) as u8,
-            tower_level_index: get_or_null(v, "towerLevelIndex").as_u64().unwrap_or(0) as u8,
+            nickname: v.nickname.clone(),
+            signature: v.signature.clone(),
+            level: v.level,
+            world_level: v.world_level,
+            achievement: v.finish_achievement_num,
+            name_card: v.name_card_id.map(NameCard),
+            tower_floor_index: v.tower_floor_index,
+            tower_level_index: v.tower_level_index,
             profile_picture: CharacterId(
-                get_or_null(get_or_null(v, "profilePicture").as_ref(), "avatarId")
-                    .as_i64()
-                    .unwrap_or(0) as u32,
+                v.profile_picture
+                    .as_ref()
+                    .and_then(|p| p.avatar_id)
+                    .unwrap_or(0),
             ),
-            avatar_info_list,
-            name_card_list,
+            avatar_info_list: v
+                .show_avatar_info_list
+                .iter()
+                .map(|a| CharacterId(a.avatar_id))
+                .collect(),
+            name_card_list: v
+                .show_name_card_id_list
+                .iter()
+                .map(|id| NameCard(*id))
+                .collect(),
         }
     }
 }
@@ -109,11 +96,12 @@ impl NameCard {
     pub fn has_value(&self) -> bool {
         self.0 > 0
     }
-    pub async fn image(&self, api: &Api) -> Result<DynamicImage, String> {
+    pub async fn image(&self, api: &Api) -> Result<DynamicImage, EnkaError> {
         if !self.has_value() {
-            return Err(String::from("None"));
+            return Err(EnkaError::AssetMissing("name card".to_string()));
         }
         let store = api.get_store();
-        api.ui_image(store.namecard_path(*self)?).await
+        let path = store.namecard_path(*self).map_err(EnkaError::Decode)?;
+        api.ui_image(path).await
     }
 }
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::{
+    api::Api,
+    character::{parse_character, Character, CharacterId},
+    error::EnkaError,
+    playerinfo::PlayerInfo,
+    raw::{parse, RawBuild, RawHoyo, RawProfile},
+};
+
+/// `hoyo_type` of Genshin Impact accounts; the other games are skipped.
+const HOYO_TYPE_GENSHIN: u8 = 0;
+
+/// An Enka.Network account, which may link several game accounts (hoyos).
+#[derive(Clone, Debug)]
+pub struct EnkaProfile {
+    pub username: String,
+    pub bio: String,
+    pub level: u32,
+    pub image_url: Option<String>,
+}
+
+impl From<RawProfile> for EnkaProfile {
+    fn from(v: RawProfile) -> Self {
+        Self {
+            username: v.username,
+            bio: v.profile.bio,
+            level: v.profile.level,
+            image_url: v.profile.image_url,
+        }
+    }
+}
+
+/// A Genshin account linked to an Enka.Network profile.
+#[derive(Clone, Debug)]
+pub struct Hoyo {
+    pub hash: String,
+    /// `None` when the owner hides the UID.
+    pub uid: Option<i32>,
+    pub region: String,
+    pub verified: bool,
+    pub order: i64,
+    pub player_info: PlayerInfo,
+}
+
+impl Hoyo {
+    fn from_raw(v: RawHoyo) -> Option<Self> {
+        if v.hoyo_type != HOYO_TYPE_GENSHIN {
+            return None;
+        }
+        Some(Self {
+            player_info: PlayerInfo::from(v.player_info.as_ref()?),
+            hash: v.hash,
+            uid: v.uid,
+            region: v.region,
+            verified: v.verified,
+            order: v.order,
+        })
+    }
+}
+
+/// A character build saved on Enka.Network.
+#[derive(Clone, Debug)]
+pub struct Build {
+    pub id: u64,
+    /// Name given by the owner. Empty for the live showcase copy.
+    pub name: String,
+    /// Whether this is the build currently shown in the in-game showcase.
+    pub live: bool,
+    pub order: i64,
+    pub character: Character,
+}
+
+impl Build {
+    fn from_raw(api: &Api, v: RawBuild) -> Result<Self, EnkaError> {
+        Ok(Self {
+            character: parse_character(api, &v.avatar_data)?,
+            id: v.id,
+            name: v.name.unwrap_or_default(),
+            live: v.live,
+            order: v.order,
+        })
+    }
+    pub fn character_id(&self) -> CharacterId {
+        self.character.id
+    }
+}
+
+pub(crate) fn parse_profile(v: &[u8]) -> Result<EnkaProfile, EnkaError> {
+    Ok(EnkaProfile::from(parse::<RawProfile>("profile", v)?))
+}
+
+pub(crate) fn parse_hoyos(v: &[u8]) -> Result<Vec<Hoyo>, EnkaError> {
+    let map: HashMap<String, RawHoyo> = parse("hoyos", v)?;
+    let mut hoyos = map
+        .into_values()
+        .filter_map(Hoyo::from_raw)
+        .collect::<Vec<_>>();
+    hoyos.sort_by_key(|h| h.order);
+    Ok(hoyos)
+}
+
+pub(crate) fn parse_builds(api: &Api, v: &[u8]) -> Result<Vec<Build>, EnkaError> {
+    let map: HashMap<String, Vec<RawBuild>> = parse("builds", v)?;
+    let mut builds = map
+        .into_values()
+        .flatten()
+        .map(|b| Build::from_raw(api, b))
+        .collect::<Result<Vec<_>, _>>()?;
+    builds.sort_by_key(|b| (b.character.id.0, b.order));
+    Ok(builds)
+}
+
+/// Enka.Network usernames only contain ASCII letters, digits, `_`, `-` and `.`.
+pub(crate) fn is_valid_username(username: &str) -> bool {
+    !username.is_empty()
+        && !username.starts_with('.')
+        && username.len() <= 64
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+pub(crate) fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty() && hash.len() <= 32 && hash.chars().all(|c| c.is_ascii_alphanumeric())
+}
@@ -0,0 +1,310 @@
+//! Typed models of the Enka.Network payloads and the bundled store files.
+//!
+//! Fields Enka.Network omits when they are zero or unset are `#[serde(default)]`,
+//! everything else is required so schema changes fail loudly in [`parse`].
+
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+
+use crate::error::EnkaError;
+
+/// Deserializes `bytes`, reporting the path of the offending field on failure.
+pub fn parse<T: DeserializeOwned>(what: &str, bytes: &[u8]) -> Result<T, EnkaError> {
+    let de = &mut serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::deserialize(de)
+        .map_err(|e| EnkaError::Decode(format!("{} at `{}`: {}", what, e.path(), e.inner())))
+}
+
+/// Text map hashes are sent as numbers in some places and strings in others.
+fn u64_or_string<'de, D: Deserializer<'de>>(de: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Hash {
+        Num(u64),
+        Str(String),
+    }
+    match Hash::deserialize(de)? {
+        Hash::Num(n) => Ok(n),
+        Hash::Str(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+fn opt_u64_or_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<u64>, D::Error> {
+    u64_or_string(de).map(Some)
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawUser {
+    #[serde(default, deserialize_with = "opt_u64_or_string")]
+    pub uid: Option<u64>,
+    pub player_info: RawPlayerInfo,
+    #[serde(default)]
+    pub avatar_info_list: Vec<RawAvatarInfo>,
+    pub ttl: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawPlayerInfo {
+    pub nickname: String,
+    pub level: u8,
+    #[serde(default)]
+    pub signature: String,
+    #[serde(default)]
+    pub world_level: u8,
+    pub name_card_id: Option<u32>,
+    #[serde(default)]
+    pub finish_achievement_num: u32,
+    #[serde(default)]
+    pub tower_floor_index: u8,
+    #[serde(default)]
+    pub tower_level_index: u8,
+    #[serde(default)]
+    pub show_avatar_info_list: Vec<RawShowAvatar>,
+    #[serde(default)]
+    pub show_name_card_id_list: Vec<u32>,
+    pub profile_picture: Option<RawProfilePicture>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawShowAvatar {
+    pub avatar_id: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawProfilePicture {
+    pub avatar_id: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawAvatarInfo {
+    pub avatar_id: u32,
+    pub skill_depot_id: Option<u32>,
+    pub prop_map: RawPropMap,
+    pub fight_prop_map: HashMap<String, f64>,
+    #[serde(default)]
+    pub talent_id_list: Vec<u32>,
+    #[serde(default)]
+    pub skill_level_map: HashMap<u32, u8>,
+    #[serde(default)]
+    pub proud_skill_extra_level_map: HashMap<u32, u8>,
+    pub equip_list: Vec<RawEquip>,
+    pub fetter_info: Option<RawFetterInfo>,
+    pub costume_id: Option<u32>,
+}
+
+/// The props a card needs. Enka.Network always sends them, but leaves out `val`
+/// when it is 0, which a level never is.
+#[derive(Deserialize, Debug)]
+pub struct RawPropMap {
+    /// Experience towards the next level.
+    #[serde(rename = "1001")]
+    pub xp: RawProp,
+    /// Ascension phase.
+    #[serde(rename = "1002")]
+    pub ascension: RawProp,
+    #[serde(rename = "4001")]
+    pub level: RawLevelProp,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawProp {
+    #[serde(default, deserialize_with = "opt_u64_or_string")]
+    pub val: Option<u64>,
+}
+
+impl RawProp {
+    pub fn value(&self) -> u64 {
+        self.val.unwrap_or(0)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawLevelProp {
+    #[serde(deserialize_with = "u64_or_string")]
+    pub val: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawFetterInfo {
+    pub exp_level: u8,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawEquip {
+    pub item_id: u32,
+    pub reliquary: Option<RawReliquary>,
+    pub weapon: Option<RawWeapon>,
+    pub flat: RawFlat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawReliquary {
+    pub level: u8,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawWeapon {
+    pub level: u8,
+    #[serde(default)]
+    pub promote_level: u8,
+    /// Missing for weapons that cannot be refined.
+    #[serde(default)]
+    pub affix_map: HashMap<String, u8>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawFlat {
+    #[serde(deserialize_with = "u64_or_string")]
+    pub name_text_map_hash: u64,
+    #[serde(default, deserialize_with = "opt_u64_or_string")]
+    pub set_name_text_map_hash: Option<u64>,
+    pub rank_level: u8,
+    pub icon: String,
+    pub equip_type: Option<String>,
+    pub reliquary_mainstat: Option<RawStat>,
+    #[serde(default)]
+    pub reliquary_substats: Vec<RawStat>,
+    #[serde(default)]
+    pub weapon_stats: Vec<RawStat>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RawStat {
+    #[serde(alias = "mainPropId")]
+    pub append_prop_id: String,
+    pub stat_value: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawProfile {
+    pub username: String,
+    pub profile: RawProfileDetail,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawProfileDetail {
+    #[serde(default)]
+    pub bio: String,
+    #[serde(default)]
+    pub level: u32,
+    pub image_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawHoyo {
+    pub hash: String,
+    /// `None` when the owner hides the UID.
+    pub uid: Option<i32>,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub order: i64,
+    #[serde(default)]
+    pub hoyo_type: u8,
+    /// Only present for Genshin accounts.
+    pub player_info: Option<RawPlayerInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawBuild {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub live: bool,
+    #[serde(default)]
+    pub order: i64,
+    pub avatar_data: RawAvatarInfo,
+}
+
+/// An entry of `characters.json`, keyed by avatar id (`{id}-{depot}` for the traveler).
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct StoreCharacter {
+    pub element: String,
+    pub consts: Vec<String>,
+    pub skill_order: Vec<u32>,
+    pub skills: HashMap<u32, String>,
+    pub proud_map: HashMap<u32, u32>,
+    pub name_text_map_hash: u32,
+    pub side_icon_name: String,
+    #[serde(default)]
+    pub costumes: HashMap<u32, StoreCostume>,
+    /// Filled from `image-characters.json` by matching `side_icon_name`.
+    #[serde(skip)]
+    pub images: Option<ImageCharacter>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct StoreCostume {
+    pub side_icon: String,
+    pub icon: String,
+    pub art: String,
+}
+
+/// An entry of `image-characters.json`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ImageCharacter {
+    pub filename_icon: String,
+    #[serde(rename = "filename_iconCard")]
+    pub filename_icon_card: String,
+    #[serde(rename = "filename_sideIcon")]
+    pub filename_side_icon: String,
+    #[serde(rename = "filename_gachaSplash")]
+    pub filename_gacha_splash: Option<String>,
+    #[serde(rename = "filename_gachaSlice")]
+    pub filename_gacha_slice: Option<String>,
+}
+
+/// An entry of `namecards.json`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct StoreNameCard {
+    pub icon: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn avatar(props: &str) -> String {
+        format!(
+            r#"{{"avatarId": 10000002, "propMap": {{{}}}, "fightPropMap": {{}}, "equipList": []}}"#,
+            props
+        )
+    }
+
+    #[test]
+    fn zero_props_have_no_val() {
+        let props = r#""1001": {"type": 1001, "ival": "0"},
+            "1002": {"type": 1002, "ival": "0"},
+            "4001": {"type": 4001, "ival": "1", "val": "1"}"#;
+        let raw: RawAvatarInfo = parse("avatar", avatar(props).as_bytes()).unwrap();
+        assert_eq!(raw.prop_map.xp.value(), 0);
+        assert_eq!(raw.prop_map.ascension.value(), 0);
+        assert_eq!(raw.prop_map.level.val, 1);
+    }
+
+    #[test]
+    fn missing_level_names_the_prop() {
+        let props = r#""1001": {"val": "0"}, "1002": {"val": "6"}"#;
+        let err = parse::<RawAvatarInfo>("avatar", avatar(props).as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("propMap"), "{}", err);
+        assert!(err.to_string().contains("4001"), "{}", err);
+        let props = r#""1001": {"val": "0"}, "1002": {"val": "6"}, "4001": {}"#;
+        let err = parse::<RawAvatarInfo>("avatar", avatar(props).as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("propMap.4001"), "{}", err);
+    }
+}
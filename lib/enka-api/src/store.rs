@@ -1,17 +1,21 @@
-use std::str::FromStr;
-
-use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
 use crate::{
-    constants::{LOC, NAMECARDS, UI_ICON},
+    constants::{CHARACTERS, IMAGE_CHARACTERS, LOC, NAMECARDS},
+    error::EnkaError,
     fight_prop::FightPropLocale,
     playerinfo::NameCard,
+    raw::{parse, ImageCharacter, StoreCharacter, StoreNameCard},
+    updater::{CHARACTERS_FILE, IMAGE_CHARACTERS_FILE, LOC_FILE, NAMECARDS_FILE},
 };
 
 pub struct Store {
-    loc: Value,
-    pub(crate) namecards: Value,
-    pub(crate) characters: Value,
+    loc: BTreeMap<String, HashMap<String, String>>,
+    namecards: HashMap<u32, StoreNameCard>,
+    pub(crate) characters: HashMap<String, StoreCharacter>,
 }
 
 impl Default for Store {
@@ -22,47 +26,84 @@ impl Default for Store {
 
 impl Store {
     pub fn new() -> Self {
-        Self {
-            loc: Value::from_str(LOC).unwrap(),
-            namecards: Value::from_str(NAMECARDS).unwrap(),
-            characters: UI_ICON.clone(),
+        Self::from_raw(
+            LOC.as_bytes(),
+            NAMECARDS.as_bytes(),
+            CHARACTERS.as_bytes(),
+            IMAGE_CHARACTERS.as_bytes(),
+        )
+        .expect("bundled store files are invalid")
+    }
+    /// Parses the store files, merging `image-characters.json` into the characters
+    /// by their side icon name.
+    pub fn from_raw(
+        loc: &[u8],
+        namecards: &[u8],
+        characters: &[u8],
+        image_characters: &[u8],
+    ) -> Result<Self, EnkaError> {
+        let mut characters: HashMap<String, StoreCharacter> = parse("characters.json", characters)?;
+        let images: HashMap<String, ImageCharacter> =
+            parse("image-characters.json", image_characters)?;
+        let images = images
+            .into_values()
+            .map(|i| (i.filename_side_icon.clone(), i))
+            .collect::<HashMap<_, _>>();
+        for character in characters.values_mut() {
+            character.images = images.get(&character.side_icon_name).cloned();
         }
+        Ok(Self {
+            loc: parse("loc.json", loc)?,
+            namecards: parse("namecards.json", namecards)?,
+            characters,
+        })
+    }
+    /// Loads the store files written by the updater from `dir`. `image-characters.json`
+    /// is optional there and falls back to the bundled copy.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, EnkaError> {
+        let dir = dir.as_ref();
+        let image_characters = match std::fs::read(dir.join(IMAGE_CHARACTERS_FILE)) {
+            Ok(data) => data,
+            Err(_) => IMAGE_CHARACTERS.as_bytes().to_vec(),
+        };
+        Self::from_raw(
+            &std::fs::read(dir.join(LOC_FILE))?,
+            &std::fs::read(dir.join(NAMECARDS_FILE))?,
+            &std::fs::read(dir.join(CHARACTERS_FILE))?,
+            &image_characters,
+        )
     }
     pub fn locale(&self, language: impl AsRef<str>, key: impl AsRef<str>) -> Option<&str> {
-        let map = self.loc.as_object()?;
-        let lang = map.get(language.as_ref())?.as_object()?;
-        lang.get(key.as_ref())?.as_str()
+        self.loc
+            .get(language.as_ref())?
+            .get(key.as_ref())
+            .map(|s| s.as_str())
     }
     pub fn is_locale_available(&self, loc: impl AsRef<str>) -> bool {
-        match self.loc.as_object() {
-            Some(v) => v.contains_key(loc.as_ref()),
-            None => false,
-        }
+        self.loc.contains_key(loc.as_ref())
     }
     pub fn locale_list(&self) -> Vec<&String> {
-        match self.loc.as_object() {
-            Some(v) => {
-                let keys = v.keys();
-                let mut list = Vec::with_capacity(keys.len());
-                for x in keys {
-                    list.push(x);
-                }
-                list
-            }
-            None => vec![],
-        }
+        self.loc.keys().collect()
     }
     pub fn namecard_path(&self, id: NameCard) -> Result<&str, String> {
-        let namecard_map = self
-            .namecards
-            .as_object()
-            .ok_or_else(|| String::from("no namecards"))?;
-        let json_value = namecard_map[&format!("{}", id.0)]
-            .as_object()
-            .ok_or_else(|| String::from("not found in map"))?;
-        json_value["icon"]
-            .as_str()
-            .ok_or_else(|| String::from("not string"))
+        self.namecards
+            .get(&id.0)
+            .map(|n| n.icon.as_str())
+            .ok_or_else(|| String::from("not found in map"))
+    }
+    /// Names of the characters in `characters.json` by avatar id. The traveler's
+    /// element variants share one id.
+    pub fn character_names(&self, language: impl AsRef<str>) -> BTreeMap<u32, &str> {
+        self.characters
+            .iter()
+            .filter_map(|(key, c)| {
+                let id = key.split('-').next()?.parse().ok()?;
+                Some((
+                    id,
+                    self.get_or_empty(&language, c.name_text_map_hash.to_string()),
+                ))
+            })
+            .collect()
     }
     pub fn get_or_empty(&self, language: impl AsRef<str>, key: impl AsRef<str>) -> &str {
         self.locale(language, key).unwrap_or("")
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::{error::EnkaError, store::Store};
+
+pub const DEFAULT_STORE_URL: &str = "https://github.com/EnkaNetwork/API-docs/raw/master/store";
+
+pub const LOC_FILE: &str = "loc.json";
+pub const NAMECARDS_FILE: &str = "namecards.json";
+pub const CHARACTERS_FILE: &str = "characters.json";
+pub const IMAGE_CHARACTERS_FILE: &str = "image-characters.json";
+
+/// The store files in the normalized form `Store` reads.
+pub struct StoreFiles {
+    pub loc: Vec<u8>,
+    pub namecards: Vec<u8>,
+    pub characters: Vec<u8>,
+}
+
+impl StoreFiles {
+    /// Downloads the store files from `base_url` (an Enka API-docs `store` directory).
+    pub async fn download(client: &Client, base_url: &str) -> Result<Self, EnkaError> {
+        let get = |path: &str| {
+            let url = format!("{}/{}", base_url, path);
+            async move {
+                log::info!("requesting {}", url);
+                let res = client.get(&url).send().await?;
+                if res.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Err(EnkaError::AssetMissing(url));
+                }
+                let res = res.error_for_status()?;
+                Ok::<_, EnkaError>(res.bytes().await?.to_vec())
+            }
+        };
+        let mut loc: Value = serde_json::from_slice(&get("gi/locs.json").await?)?;
+        let common: Value = serde_json::from_slice(&get("loc.json").await?)?;
+        merge(&mut loc, common);
+        let namecards = String::from_utf8_lossy(&get("gi/namecards.json").await?)
+            .replace("/ui/", "")
+            .replace(".jpg", "")
+            .replace("Icon", "icon");
+        let characters = String::from_utf8_lossy(&get("gi/avatars.json").await?)
+            .replace("/ui/", "")
+            .replace(".png", "");
+        let files = Self {
+            loc: serde_json::to_vec(&loc)?,
+            namecards: namecards.into_bytes(),
+            characters: characters.into_bytes(),
+        };
+        // refuse to hand out files the store cannot read
+        files.parse()?;
+        Ok(files)
+    }
+    /// Writes the files into `dir`, replacing each one atomically.
+    pub async fn write_to(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        tokio::fs::create_dir_all(dir).await?;
+        for (name, data) in [
+            (LOC_FILE, &self.loc),
+            (NAMECARDS_FILE, &self.namecards),
+            (CHARACTERS_FILE, &self.characters),
+        ] {
+            let tmp = dir.join(format!("{}.tmp", name));
+            tokio::fs::write(&tmp, data).await?;
+            tokio::fs::rename(&tmp, dir.join(name)).await?;
+        }
+        Ok(())
+    }
+    pub fn parse(&self) -> Result<Store, EnkaError> {
+        Store::from_raw(
+            &self.loc,
+            &self.namecards,
+            &self.characters,
+            crate::constants::IMAGE_CHARACTERS.as_bytes(),
+        )
+    }
+}
+
+/// Recursive object merge where `other` wins, like jq's `*`.
+fn merge(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Object(base), Value::Object(other)) => {
+            for (k, v) in other {
+                match base.get_mut(&k) {
+                    Some(b) => merge(b, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, other) => *base = other,
+    }
+}
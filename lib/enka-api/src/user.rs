@@ -3,14 +3,15 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use serde_json::Value;
-
 use crate::{
     api::Api,
     character::{parse_character, Character, CharacterId},
+    error::EnkaError,
     playerinfo::PlayerInfo,
+    raw::{parse, RawUser},
 };
 
+#[derive(Clone)]
 pub struct ApiRawUser {
     contents: Vec<u8>,
     uid: i32,
@@ -34,41 +35,20 @@ impl ApiRawUser {
     pub fn contents(&self) -> &[u8] {
         &self.contents
     }
-    pub fn resolve(&self, api: &Api) -> Result<ApiUser, String> {
-        let res: Result<Value, serde_json::Error> = serde_json::from_slice(&self.contents);
-        match res {
-            Ok(val) => match val.as_object() {
-                Some(root) => {
-                    let profile = PlayerInfo::from(
-                        root.get("playerInfo")
-                            .ok_or_else(|| String::from("no player info"))?,
-                    );
-                    let mut characters = HashMap::new();
-                    if let Some(avatar_info_list) = root.get("avatarInfoList") {
-                        if let Some(map) = avatar_info_list.as_array() {
-                            for id in map {
-                                if let Some(c) = parse_character(api, id) {
-                                    characters.insert(c.id, c);
-                                }
-                            }
-                        }
-                    }
-                    let ttl = match root.get("ttl") {
-                        Some(ttl) => ttl.as_u64().unwrap_or(5 * 60) as u32,
-                        None => 5 * 60,
-                    };
-                    Ok(ApiUser {
-                        uid: self.uid,
-                        ttl,
-                        lastupdate: self.lastupdate,
-                        profile,
-                        characters,
-                    })
-                }
-                None => Err(String::from("NoRootValue")),
-            },
-            Err(e) => Err(format!("{}", e)),
-        }
+    pub fn resolve(&self, api: &Api) -> Result<ApiUser, EnkaError> {
+        let raw: RawUser = parse("user", &self.contents)?;
+        let characters = raw
+            .avatar_info_list
+            .iter()
+            .map(|c| parse_character(api, c).map(|c| (c.id, c)))
+            .collect::<Result<_, _>>()?;
+        Ok(ApiUser {
+            uid: self.uid,
+            ttl: raw.ttl.unwrap_or(5 * 60),
+            lastupdate: self.lastupdate,
+            profile: PlayerInfo::from(&raw.player_info),
+            characters,
+        })
     }
 }
 
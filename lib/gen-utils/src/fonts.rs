@@ -0,0 +1,22 @@
+//! Fonts bundled with the card generators.
+
+use once_cell::sync::Lazy;
+use rusttype::Font;
+
+pub static GENSHIN: Lazy<Font<'static>> =
+    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../../assets/font.ttf")).unwrap());
+pub static HSR: Lazy<Font<'static>> =
+    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../../assets/hsr-font.ttf")).unwrap());
+
+/// Names card layouts can refer to the bundled fonts by.
+pub const BUNDLED: [&str; 2] = ["genshin", "hsr"];
+
+/// The bundled font called `name`, one of `BUNDLED`. It is only loaded once
+/// dereferenced.
+pub fn bundled(name: &str) -> Option<&'static Lazy<Font<'static>>> {
+    match name {
+        "genshin" => Some(&GENSHIN),
+        "hsr" => Some(&HSR),
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+//! Score cutoffs of the grade badges, per game and named threshold set.
+//!
+//! Each set maps `total` and every artifact/relic part to its cutoffs. Unless the
+//! caller picks a set, the generators use the one keyed by the counter's `from_str`
+//! name (`custom` for custom weights) and fall back to `default`.
+//!
+//! Sets other than `default` scale its cutoffs by how much a well rolled piece
+//! scores under that counter compared to the attack preset, so counters weighting
+//! fewer or cheaper stats still reach SS.
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+pub const GRADE_THRESHOLDS: &str = include_str!("../../../assets/grade-thresholds.json");
+/// Format version of the grade thresholds file this build understands.
+pub const GRADE_THRESHOLDS_VERSION: u32 = 1;
+
+pub const GENSHIN_PARTS: [&str; 6] = ["total", "flower", "feather", "sands", "goblet", "circlet"];
+pub const HSR_PARTS: [&str; 7] = ["total", "head", "hands", "body", "feet", "sphere", "rope"];
+
+pub static GRADES: Lazy<GradeThresholds> = Lazy::new(|| {
+    GradeThresholds::parse(GRADE_THRESHOLDS).expect("bundled grade thresholds are invalid")
+});
+
+/// Cutoffs keyed by `total` or part name.
+pub type GradeSet = BTreeMap<String, Thresholds>;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Thresholds {
+    pub ss: f64,
+    pub s: f64,
+    pub a: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GradeThresholds {
+    pub version: u32,
+    pub genshin: BTreeMap<String, GradeSet>,
+    pub hsr: BTreeMap<String, GradeSet>,
+}
+
+impl GradeThresholds {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let grades: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if grades.version != GRADE_THRESHOLDS_VERSION {
+            return Err(format!(
+                "unsupported grade thresholds version {} (expected {})",
+                grades.version, GRADE_THRESHOLDS_VERSION
+            ));
+        }
+        validate("genshin", &grades.genshin, &GENSHIN_PARTS)?;
+        validate("hsr", &grades.hsr, &HSR_PARTS)?;
+        Ok(grades)
+    }
+}
+
+fn validate(game: &str, sets: &BTreeMap<String, GradeSet>, parts: &[&str]) -> Result<(), String> {
+    if !sets.contains_key("default") {
+        return Err(format!("{} has no default threshold set", game));
+    }
+    for (name, set) in sets {
+        for part in parts {
+            let t = set
+                .get(*part)
+                .ok_or_else(|| format!("{}.{} is missing {}", game, name, part))?;
+            if !(t.ss >= t.s && t.s >= t.a) {
+                return Err(format!(
+                    "{}.{}.{} is not ordered ss >= s >= a",
+                    game, name, part
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The set named `counter`, or `default`.
+pub fn grade_set_for<'a>(sets: &'a BTreeMap<String, GradeSet>, counter: &str) -> &'a GradeSet {
+    sets.get(counter).unwrap_or_else(|| &sets["default"])
+}
@@ -1,3 +1,9 @@
+pub mod fonts;
+pub mod grades;
+pub mod profiles;
+pub mod style;
+pub mod weights;
+
 use image::DynamicImage;
 const GRADES_B: &[u8; 45056] = include_bytes!("../../../assets/grades/B.png");
 const GRADES_A: &[u8; 39600] = include_bytes!("../../../assets/grades/A.png");
@@ -0,0 +1,42 @@
+//! Per-character default score counters, shared by the Genshin and HSR generators.
+//!
+//! Counters are kept as strings here and parsed by each generator's `ScoreCounter`.
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+pub const SCORE_PROFILES: &str = include_str!("../../../assets/score-profiles.json");
+/// Format version of the score profiles file this build understands.
+pub const SCORE_PROFILES_VERSION: u32 = 1;
+
+pub static PROFILES: Lazy<ScoreProfiles> =
+    Lazy::new(|| ScoreProfiles::parse(SCORE_PROFILES).expect("bundled score profiles are invalid"));
+
+#[derive(Deserialize, Debug)]
+pub struct ScoreProfiles {
+    pub version: u32,
+    pub genshin: BTreeMap<u32, ScoreProfile>,
+    pub hsr: BTreeMap<u32, ScoreProfile>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ScoreProfile {
+    /// Only there to keep the file readable.
+    pub name: String,
+    pub counter: String,
+}
+
+impl ScoreProfiles {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let profiles: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if profiles.version != SCORE_PROFILES_VERSION {
+            return Err(format!(
+                "unsupported score profiles version {} (expected {})",
+                profiles.version, SCORE_PROFILES_VERSION
+            ));
+        }
+        Ok(profiles)
+    }
+}
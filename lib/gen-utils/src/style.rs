@@ -0,0 +1,63 @@
+//! Parts shared by the card styles of every game. Each generator has its own
+//! input type and `CardStyle` enum built on these.
+
+use async_trait::async_trait;
+use image::DynamicImage;
+
+/// One card design, drawing from its game's `Input`.
+#[async_trait]
+pub trait CardRenderer<Input: Send>: Send + Sync {
+    /// Name used to select the style, e.g. in the `style` query parameter.
+    fn name(&self) -> &'static str;
+    async fn render(&self, input: Input) -> Option<DynamicImage>
+    where
+        Input: 'async_trait;
+}
+
+/// The selectable styles of one game.
+pub trait Styles: Copy + PartialEq + 'static {
+    /// Every style, the default one first.
+    const ALL: &'static [Self];
+    fn name(&self) -> &'static str;
+}
+
+/// Looks a style up by name, ignoring case. `default` names the first style.
+pub fn parse_style<S: Styles>(s: &str) -> Result<S, String> {
+    let s = s.to_lowercase();
+    if s == "default" {
+        return Ok(S::ALL[0]);
+    }
+    S::ALL
+        .iter()
+        .copied()
+        .find(|style| style.name() == s)
+        .ok_or_else(|| format!("{} is not CardStyle", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Style {
+        Wide,
+        Tall,
+    }
+
+    impl Styles for Style {
+        const ALL: &'static [Self] = &[Style::Wide, Style::Tall];
+        fn name(&self) -> &'static str {
+            match self {
+                Style::Wide => "wide",
+                Style::Tall => "tall",
+            }
+        }
+    }
+
+    #[test]
+    fn parses_names_and_default() {
+        assert_eq!(parse_style("Tall"), Ok(Style::Tall));
+        assert_eq!(parse_style("default"), Ok(Style::Wide));
+        assert!(parse_style::<Style>("square").is_err());
+    }
+}
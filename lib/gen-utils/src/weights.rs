@@ -0,0 +1,101 @@
+//! Parser of the `key=weight` lists custom score counters are written in.
+//!
+//! Both games count custom weights in rolls: every substat is divided by its
+//! highest single 5-star roll, so a weight of 1 values one max roll of any stat
+//! the same. `crit=w` is shorthand for `cr=w,cd=w`.
+
+/// Largest weight accepted for a single stat.
+pub const MAX_WEIGHT: f64 = 10.0;
+
+/// Parses comma separated `key=weight` pairs into weights ordered like `keys`.
+///
+/// Keys are case insensitive and unlisted keys keep a weight of 0. At least one
+/// weight must be greater than 0.
+pub fn parse_weights<const N: usize>(s: &str, keys: &[&str; N]) -> Result<[f64; N], String> {
+    let mut weights = [0.0; N];
+    for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("{} is not a key=weight pair", pair))?;
+        let key = key.trim().to_lowercase();
+        let value = value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| (0.0..=MAX_WEIGHT).contains(v))
+            .ok_or_else(|| {
+                format!(
+                    "weight of {} must be a number between 0 and {}",
+                    key, MAX_WEIGHT
+                )
+            })?;
+        let targets = match key.as_str() {
+            "crit" => vec!["cr", "cd"],
+            key => vec![key],
+        };
+        for target in targets {
+            let i = keys
+                .iter()
+                .position(|k| *k == target)
+                .ok_or_else(|| format!("{} is not a scorable stat", key))?;
+            weights[i] = value;
+        }
+    }
+    if weights.iter().all(|w| *w == 0.0) {
+        return Err("at least one weight must be greater than 0".to_string());
+    }
+    Ok(weights)
+}
+
+/// Writes the non-zero `weights` back as `key=weight` pairs.
+pub fn format_weights(keys: &[&str], weights: &[f64]) -> String {
+    keys.iter()
+        .zip(weights)
+        .filter(|(_, w)| **w != 0.0)
+        .map(|(k, w)| format!("{}={}", k, w))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: [&str; 4] = ["atk%", "cr", "cd", "spd"];
+
+    #[test]
+    fn parses_pairs_in_key_order() {
+        assert_eq!(
+            parse_weights(" SPD=2 , atk%=0.5,", &KEYS),
+            Ok([0.5, 0.0, 0.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn crit_sets_both_crit_stats() {
+        assert_eq!(parse_weights("crit=1.5", &KEYS), Ok([0.0, 1.5, 1.5, 0.0]));
+        assert_eq!(
+            parse_weights("crit=1,cd=0.5", &KEYS),
+            Ok([0.0, 1.0, 0.5, 0.0])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_format() {
+        let weights = parse_weights("cr=1,cd=1,spd=0.25", &KEYS).unwrap();
+        let formatted = format_weights(&KEYS, &weights);
+        assert_eq!(formatted, "cr=1,cd=1,spd=0.25");
+        assert_eq!(parse_weights(&formatted, &KEYS), Ok(weights));
+    }
+
+    #[test]
+    fn rejects_invalid_lists() {
+        assert!(parse_weights("spd", &KEYS).is_err());
+        assert!(parse_weights("em=1", &KEYS).is_err());
+        assert!(parse_weights("spd=fast", &KEYS).is_err());
+        assert!(parse_weights("spd=-1", &KEYS).is_err());
+        assert!(parse_weights("spd=11", &KEYS).is_err());
+        assert!(parse_weights("spd=0", &KEYS).is_err());
+        assert!(parse_weights("", &KEYS).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! Reports characters in `characters.json` and in the StarRailRes character
+//! index without a default score profile, profiles whose counter does not parse
+//! and invalid grade thresholds. Exits with 1 when anything is found.
+//!
+//! Takes an optional store directory and an optional StarRailRes
+//! `characters.json`; the bundled store and the published index are used
+//! otherwise.
+
+use gen::{
+    default::{invalid_defaults, missing_defaults},
+    enka_api::store::Store,
+};
+use gen_utils::grades::{GradeThresholds, GRADE_THRESHOLDS};
+use hsr_gen::{
+    default::{invalid_defaults as invalid_hsr_defaults, missing_defaults as missing_hsr_defaults},
+    mihomo_api::{api::Api, store::parse_character_names},
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let store = match args.next() {
+        Some(dir) => Store::from_dir(&dir).expect("failed to load store"),
+        None => Store::new(),
+    };
+    let hsr_names = match args.next() {
+        Some(path) => {
+            let data = std::fs::read(&path).expect("failed to read character index");
+            parse_character_names(&data)
+        }
+        None => Api::new().character_names().await,
+    }
+    .expect("failed to load hsr character index");
+    let missing = missing_defaults(&store)
+        .into_iter()
+        .chain(missing_hsr_defaults(&hsr_names))
+        .collect::<Vec<_>>();
+    for (id, name) in &missing {
+        println!("missing: {} {}", id, name);
+    }
+    let invalid = invalid_defaults()
+        .into_iter()
+        .chain(invalid_hsr_defaults())
+        .collect::<Vec<_>>();
+    for (id, e) in &invalid {
+        println!("invalid: {} {}", id, e);
+    }
+    let grades = GradeThresholds::parse(GRADE_THRESHOLDS);
+    if let Err(e) = &grades {
+        println!("invalid grade thresholds: {}", e);
+    }
+    if !missing.is_empty() || !invalid.is_empty() || grades.is_err() {
+        std::process::exit(1);
+    }
+    println!("all characters have a score profile");
+}
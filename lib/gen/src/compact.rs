@@ -0,0 +1,302 @@
+use enka_api::character::Stats;
+use image::{
+    imageops::{crop_imm, overlay, FilterType::Triangle},
+    DynamicImage, Rgba,
+};
+use imageproc::drawing::{draw_text_mut, text_size};
+use rusttype::Scale;
+
+use crate::{
+    constants::{self, FONT},
+    gen::{
+        draw_text_resized, get_artifacts, get_score, is_percent, round_to_1_decimal_places,
+        ArtifactMetrics, TotalMetric,
+    },
+    locale,
+    style::CardInput,
+};
+
+const WIDTH: u32 = 720;
+const HEIGHT: u32 = 1280;
+const MARGIN: i32 = 30;
+const STATS_Y: i32 = 580;
+const ARTIFACTS_Y: i32 = 745;
+const ARTIFACT_ROW: i32 = 90;
+const FOOTER_Y: i32 = 1205;
+
+/// Draws the compact portrait card: splash on top, then stats, one row per
+/// artifact and the total score at the bottom.
+pub(crate) async fn render(input: CardInput<'_>) -> Option<DynamicImage> {
+    let CardInput {
+        data,
+        api,
+        lang: raw_lang,
+        icons,
+        counter,
+        options,
+    } = input;
+    let lang = &raw_lang.to_string();
+    let font = &FONT;
+    let white = Rgba([255, 255, 255, 255]);
+    let gray = Rgba([240, 240, 240, 200]);
+
+    // the landscape base, cropped to its center and scaled to portrait
+    let base = constants::get_base_image(&data.element)?;
+    let crop_w = base.height() * WIDTH / HEIGHT;
+    let base = crop_imm(&base, (base.width() - crop_w) / 2, 0, crop_w, base.height()).to_image();
+    let mut image = DynamicImage::ImageRgba8(base).resize_exact(WIDTH, HEIGHT, Triangle);
+
+    let splash = data.image_gacha_splash(api).await?;
+    let splash = splash.resize(960, 480, Triangle);
+    overlay(
+        &mut image,
+        &splash,
+        (WIDTH as i64 - splash.width() as i64) / 2,
+        -20,
+    );
+
+    let name = data.name(api, lang).ok()?;
+    draw_text_resized(
+        &mut image,
+        white,
+        MARGIN,
+        420,
+        Scale::uniform(48.0),
+        font,
+        &name,
+        WIDTH - 2 * MARGIN as u32,
+    );
+    let constellation = data.talents().iter().filter(|c| c.is_unlock()).count();
+    let skills = data
+        .skills()
+        .iter()
+        .map(|s| (s.level() + s.extra_level()).to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    let level = format!(
+        "Lv.{},{}  C{}  {}",
+        data.level,
+        data.friendship(),
+        constellation,
+        skills
+    );
+    draw_text_mut(
+        &mut image,
+        white,
+        MARGIN,
+        472,
+        Scale::uniform(28.0),
+        font,
+        &level,
+    );
+
+    let weapon = data.weapon();
+    let weapon_line = format!(
+        "{}  Lv.{} R{}",
+        weapon.name(api, lang)?,
+        weapon.level,
+        weapon.refinement + 1
+    );
+    let scale = Scale::uniform(24.0);
+    draw_text_resized(
+        &mut image,
+        white,
+        MARGIN,
+        510,
+        scale,
+        font,
+        &weapon_line,
+        WIDTH - 2 * MARGIN as u32,
+    );
+    let sets = data
+        .artifact_sets()
+        .iter()
+        .map(|s| format!("{}pc {}", s.count, s.name(api, lang).unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join(" / ");
+    draw_text_resized(
+        &mut image,
+        gray,
+        MARGIN,
+        542,
+        scale,
+        font,
+        &sets,
+        WIDTH - 2 * MARGIN as u32,
+    );
+
+    let status = data.fight_prop();
+    let percent = |v: f64| format!("{}%", round_to_1_decimal_places(v * 100.0));
+    let statuslist = [
+        (
+            Stats::Hp,
+            (status.display_max_hp.round() as u32).to_string(),
+        ),
+        (
+            Stats::Attack,
+            (status.display_attack.round() as u32).to_string(),
+        ),
+        (
+            Stats::Defense,
+            (status.display_defense.round() as u32).to_string(),
+        ),
+        (
+            Stats::ElementMastery,
+            (status.elemental_mastery.round() as i64).to_string(),
+        ),
+        (Stats::Critical, percent(status.critical_rate)),
+        (Stats::CriticalHurt, percent(status.critical_damage)),
+        (Stats::ChargeEfficiency, percent(status.energy_recharge)),
+        (
+            Stats::ElementAddHurt(data.element),
+            percent(*status.damage_bonus.get(&data.element).unwrap_or(&0.0)),
+        ),
+    ];
+    let scale = Scale::uniform(24.0);
+    let column_w = (WIDTH as i32 - 2 * MARGIN) / 2;
+    for (index, (stat, value)) in statuslist.iter().enumerate() {
+        let x = MARGIN + column_w * (index as i32 / 4);
+        let y = STATS_Y + 38 * (index as i32 % 4);
+        draw_text_resized(
+            &mut image,
+            gray,
+            x,
+            y,
+            scale,
+            font,
+            &stat.name(api, lang)?,
+            200,
+        );
+        let w = text_size(scale, font, value).0;
+        draw_text_mut(
+            &mut image,
+            white,
+            x + column_w - 20 - w,
+            y,
+            scale,
+            font,
+            value,
+        );
+    }
+
+    let grades = options.grades.unwrap_or_else(|| counter.grades());
+    let mut total = 0.0;
+    for (index, artifact) in get_artifacts(data.reliquarys()).into_iter().enumerate() {
+        let Some(artifact) = artifact else {
+            continue;
+        };
+        let y = ARTIFACTS_Y + ARTIFACT_ROW * index as i32;
+        let icon = artifact.image_icon(api).await.ok()?;
+        let icon = icon.resize_exact(80, 80, Triangle);
+        overlay(&mut image, &icon, 20, y.into());
+
+        let main = artifact.main_stats;
+        let main_value = if is_percent(&main.0) {
+            format!("{}%", round_to_1_decimal_places(main.1))
+        } else {
+            main.1.to_string()
+        };
+        let mut main_icon = main.0.image(icons, 1.2)?;
+        for p in main_icon.pixels_mut() {
+            p.0 = [255, 255, 255, p.0[3]];
+        }
+        overlay(&mut image, &main_icon, 110, (y + 14).into());
+        let scale = Scale::uniform(28.0);
+        draw_text_mut(&mut image, white, 140, y + 10, scale, font, &main_value);
+        let level = format!("+{}", artifact.level);
+        let scale = Scale::uniform(20.0);
+        draw_text_mut(&mut image, gray, 140, y + 48, scale, font, &level);
+
+        let (score, used) = get_score(artifact, &counter);
+        total += score;
+        for (i, sub) in artifact.sub_stats.iter().enumerate() {
+            let Some(sub) = sub else {
+                continue;
+            };
+            let x = 250 + 170 * (i as i32 % 2);
+            let sy = y + 10 + 38 * (i as i32 / 2);
+            let color = if used.contains(&sub.0.id().to_string()) {
+                white
+            } else {
+                gray
+            };
+            let mut sub_icon = sub.0.image(icons, 1.2)?;
+            for p in sub_icon.pixels_mut() {
+                p.0 = [255, 255, 255, p.0[3]];
+            }
+            overlay(&mut image, &sub_icon, x.into(), (sy + 2).into());
+            let value = if is_percent(&sub.0) {
+                format!("{}%", round_to_1_decimal_places(sub.1))
+            } else {
+                sub.1.to_string()
+            };
+            draw_text_mut(&mut image, color, x + 28, sy, scale, font, &value);
+        }
+
+        let grade = constants::get_grade_image(score, Some(artifact.position), grades)?;
+        overlay(&mut image, &grade, 645, (y + 5).into());
+        let score = round_to_1_decimal_places(score);
+        let scale = Scale::uniform(26.0);
+        let w = text_size(scale, font, &score).0;
+        draw_text_mut(&mut image, white, 690 - w, y + 55, scale, font, &score);
+    }
+
+    let metrics = ArtifactMetrics::of(data);
+    let (label, value) = match options.total {
+        TotalMetric::Score => (
+            locale::json!({ "en": "Total Score", "ja": "総合スコア" }),
+            round_to_1_decimal_places(total),
+        ),
+        TotalMetric::CritValue => (
+            locale::json!({ "en": "Crit Value", "ja": "会心値" }),
+            round_to_1_decimal_places(metrics.crit_value),
+        ),
+        TotalMetric::RollValue => (
+            locale::json!({ "en": "Roll Value", "ja": "ロール値" }),
+            format!("{}%", metrics.roll_value.round()),
+        ),
+    };
+    let label = locale::Locale::from(label).get(raw_lang).to_string();
+    let scale = Scale::uniform(26.0);
+    draw_text_mut(
+        &mut image,
+        white,
+        MARGIN,
+        FOOTER_Y + 14,
+        scale,
+        font,
+        &label,
+    );
+    let label_w = text_size(scale, font, &label).0;
+    let value_scale = Scale::uniform(44.0);
+    let value_x = MARGIN + label_w + 15;
+    draw_text_mut(
+        &mut image,
+        white,
+        value_x,
+        FOOTER_Y,
+        value_scale,
+        font,
+        &value,
+    );
+    let value_w = text_size(value_scale, font, &value).0;
+    let grade = constants::get_grade_image(total, None, grades)?;
+    overlay(
+        &mut image,
+        &grade,
+        (value_x + value_w + 15).into(),
+        (FOOTER_Y + 5).into(),
+    );
+    let kind = counter.to_string_locale(lang);
+    let w = text_size(scale, font, &kind).0;
+    draw_text_mut(
+        &mut image,
+        white,
+        WIDTH as i32 - MARGIN - w,
+        FOOTER_Y + 14,
+        scale,
+        font,
+        &kind,
+    );
+    Some(image)
+}
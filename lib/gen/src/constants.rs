@@ -1,14 +1,13 @@
 use enka_api::{character::ReliquaryType, element::Element};
+pub use gen_utils::fonts::GENSHIN as FONT;
 use gen_utils::get_grade_image as r_get_grade_image;
 use gen_utils::get_rarity_image as r_get_rarity_image;
+use gen_utils::grades::{GradeSet, Thresholds};
 use image::imageops::FilterType::Triangle;
 use image::load_from_memory;
 use image::{imageops::FilterType, DynamicImage};
 use once_cell::sync::Lazy;
-use rusttype::Font;
 
-pub static FONT: Lazy<Font> =
-    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../../assets/font.ttf")).unwrap());
 static BASE_ELECTRIC: Lazy<DynamicImage> =
     Lazy::new(|| load_from_memory(include_bytes!("../../../assets/base/electric.png")).unwrap());
 static BASE_FIRE: Lazy<DynamicImage> =
@@ -38,43 +37,16 @@ pub fn get_base_image(kind: &Element) -> Option<DynamicImage> {
     }
 }
 
-pub struct Scores {
-    ss: f64,
-    s: f64,
-    a: f64,
-}
-
-pub fn get_scores_for_part(part: Option<ReliquaryType>) -> Scores {
-    if part.is_none() {
-        return Scores {
-            ss: 220.0,
-            s: 200.0,
-            a: 180.0,
-        };
-    }
-    let part = part.unwrap();
-    match part {
-        ReliquaryType::Circlet => Scores {
-            ss: 40.0,
-            s: 35.0,
-            a: 30.0,
-        },
-        ReliquaryType::Flower | ReliquaryType::Feather => Scores {
-            ss: 50.0,
-            s: 45.0,
-            a: 40.0,
-        },
-        ReliquaryType::Sands => Scores {
-            ss: 45.0,
-            s: 40.0,
-            a: 35.0,
-        },
-        ReliquaryType::Goblet => Scores {
-            ss: 45.0,
-            s: 40.0,
-            a: 37.0,
-        },
-    }
+pub fn get_scores_for_part(grades: &GradeSet, part: Option<ReliquaryType>) -> Option<Thresholds> {
+    let key = match part {
+        None => "total",
+        Some(ReliquaryType::Flower) => "flower",
+        Some(ReliquaryType::Feather) => "feather",
+        Some(ReliquaryType::Sands) => "sands",
+        Some(ReliquaryType::Goblet) => "goblet",
+        Some(ReliquaryType::Circlet) => "circlet",
+    };
+    grades.get(key).copied()
 }
 static ELECTRIC: Lazy<DynamicImage> =
     Lazy::new(|| load_from_memory(include_bytes!("../../../assets/clock/electric.png")).unwrap());
@@ -131,8 +103,12 @@ pub fn get_clock_image(f: impl AsRef<str>, locked: bool) -> Option<&'static Dyna
     })
 }
 
-pub fn get_grade_image(score: f64, part: Option<ReliquaryType>) -> Option<DynamicImage> {
-    let scores = get_scores_for_part(part);
+pub fn get_grade_image(
+    score: f64,
+    part: Option<ReliquaryType>,
+    grades: &GradeSet,
+) -> Option<DynamicImage> {
+    let scores = get_scores_for_part(grades, part)?;
     let grade = r_get_grade_image(if score >= scores.ss {
         "SS"
     } else if score >= scores.s {
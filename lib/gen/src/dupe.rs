@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::Deserialize;
 
 use crate::constants::{DUPE, SUBOP};
-use enka_api::character::{Reliquary, Stats};
+use enka_api::character::{Reliquary, Stats, StatsValue};
 use once_cell::sync::Lazy;
 
 static DUPE_LIST: Lazy<Dupe> = Lazy::new(|| serde_json::from_str(DUPE).unwrap());
@@ -24,18 +24,18 @@ pub struct Dupe {
 }
 
 impl Dupe {
-    pub fn get(&self, s: &Stats) -> Option<HashMap<String, Vec<Vec<f64>>>> {
+    pub fn get(&self, s: &Stats) -> Option<&HashMap<String, Vec<Vec<f64>>>> {
         Some(match s {
-            Stats::Critical => self.crit_per.clone(),
-            Stats::CriticalHurt => self.crit_dmg.clone(),
-            Stats::ChargeEfficiency => self.charge_per.clone(),
-            Stats::DefensePercent => self.def_per.clone(),
-            Stats::AttackPercent => self.atk_per.clone(),
-            Stats::HpPercent => self.hp_per.clone(),
-            Stats::Hp => self.hp.clone(),
-            Stats::Attack => self.atk.clone(),
-            Stats::Defense => self.def.clone(),
-            Stats::ElementMastery => self.mastery.clone(),
+            Stats::Critical => &self.crit_per,
+            Stats::CriticalHurt => &self.crit_dmg,
+            Stats::ChargeEfficiency => &self.charge_per,
+            Stats::DefensePercent => &self.def_per,
+            Stats::AttackPercent => &self.atk_per,
+            Stats::HpPercent => &self.hp_per,
+            Stats::Hp => &self.hp,
+            Stats::Attack => &self.atk,
+            Stats::Defense => &self.def,
+            Stats::ElementMastery => &self.mastery,
             _ => return None,
         })
     }
@@ -56,59 +56,168 @@ pub struct Subop {
 }
 
 impl Subop {
-    pub fn get(&self, s: &Stats) -> Option<HashMap<String, Vec<f64>>> {
+    pub fn get(&self, s: &Stats) -> Option<&HashMap<String, Vec<f64>>> {
         Some(match s {
-            Stats::Critical => self.crit_per.clone(),
-            Stats::CriticalHurt => self.crit_dmg.clone(),
-            Stats::ChargeEfficiency => self.charge_per.clone(),
-            Stats::DefensePercent => self.def_per.clone(),
-            Stats::AttackPercent => self.atk_per.clone(),
-            Stats::HpPercent => self.hp_per.clone(),
-            Stats::Hp => self.hp.clone(),
-            Stats::Attack => self.atk.clone(),
-            Stats::Defense => self.def.clone(),
-            Stats::ElementMastery => self.mastery.clone(),
+            Stats::Critical => &self.crit_per,
+            Stats::CriticalHurt => &self.crit_dmg,
+            Stats::ChargeEfficiency => &self.charge_per,
+            Stats::DefensePercent => &self.def_per,
+            Stats::AttackPercent => &self.atk_per,
+            Stats::HpPercent => &self.hp_per,
+            Stats::Hp => &self.hp,
+            Stats::Attack => &self.atk,
+            Stats::Defense => &self.def,
+            Stats::ElementMastery => &self.mastery,
             _ => return None,
         })
     }
 }
 
-pub fn resolve_op(art: &Reliquary) -> Option<Vec<Vec<f64>>> {
-    let sub = art.sub_stats.iter().collect::<Vec<_>>();
-    let mut max_count = (art.rarity + art.level / 4 - 1) as usize;
-    let mut dupes = vec![vec![vec![]]; 4];
-    let mut subops = vec![vec![0.0; 6]; 4];
-    for (index, sub) in sub.iter().enumerate() {
-        if let Some(sub) = sub {
-            let trim = trim(sub.1);
-            let not_trim = sub.1.to_string();
-            let dupe_list = DUPE_LIST.get(&sub.0)?;
-            let dupe = dupe_list.get(&trim).or(dupe_list.get(&not_trim));
-            let subop_list = SUBOP_LIST.get(&sub.0)?;
-            let mut subop = subop_list.get(&trim).or(subop_list.get(&not_trim))?;
-            if let Some(dupe) = dupe {
-                dupes[index] = dupe.clone();
-                let dupe = dupe.iter().min_by_key(|d| d.len())?;
-                subop = dupe;
+/// Every way `value` of `stat` can be split into 5-star rolls.
+fn candidates(stat: &Stats, value: f64) -> Option<Vec<Vec<f64>>> {
+    let dupe = DUPE_LIST.get(stat)?;
+    let subop = SUBOP_LIST.get(stat)?;
+    let mut found: Vec<Vec<f64>> = Vec::new();
+    for key in [trim(value), value.to_string()] {
+        let alternatives = dupe.get(&key).into_iter().flatten();
+        for split in alternatives.chain(subop.get(&key)) {
+            if !found.contains(split) {
+                found.push(split.clone());
             }
-            subops[index] = subop.clone();
-            max_count -= subop.len();
         }
     }
-    for (index, dupe) in dupes.iter_mut().enumerate() {
-        if dupe.first().map(|f| f.is_empty()).unwrap_or(false) {
-            continue;
+    (!found.is_empty()).then_some(found)
+}
+
+/// Splits the substats of a 5-star artifact at `level` into their rolls, one
+/// entry per slot (empty for an empty slot).
+///
+/// The rolls must add up to the initial substats (3 or 4) plus one per 4
+/// levels. `None` when no split or more than one split fits.
+pub fn resolve_rolls(level: u8, sub_stats: &[Option<StatsValue>; 4]) -> Option<Vec<Vec<f64>>> {
+    let upgrades = (level / 4) as usize;
+    let present = sub_stats.iter().flatten().count();
+    let totals = if present < 4 || upgrades == 0 {
+        vec![present + upgrades]
+    } else {
+        vec![3 + upgrades, 4 + upgrades]
+    };
+    let candidates = sub_stats
+        .iter()
+        .map(|sub| match sub {
+            Some(StatsValue(stat, value)) => candidates(stat, *value),
+            None => Some(vec![vec![]]),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let mut found = None;
+    let mut choice = vec![0; candidates.len()];
+    loop {
+        let split = choice
+            .iter()
+            .zip(&candidates)
+            .map(|(i, c)| &c[*i])
+            .collect::<Vec<_>>();
+        if totals.contains(&split.iter().map(|s| s.len()).sum()) {
+            if found.is_some() {
+                return None;
+            }
+            found = Some(split.into_iter().cloned().collect());
         }
-        dupe.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        for dupe in dupe {
-            if max_count == 0 {
+        let mut slot = 0;
+        loop {
+            if slot == choice.len() {
+                return found;
+            }
+            choice[slot] += 1;
+            if choice[slot] < candidates[slot].len() {
                 break;
             }
-            subops[index] = dupe.clone();
-            max_count -= 1;
+            choice[slot] = 0;
+            slot += 1;
         }
     }
-    Some(subops)
+}
+
+/// Rolls of each substat slot, see `resolve_rolls`. Only 5-star artifacts are
+/// supported, matching `dupe.json`/`subop.json`.
+pub fn resolve_op(art: &Reliquary) -> Option<Vec<Vec<f64>>> {
+    if art.rarity != 5 {
+        return None;
+    }
+    resolve_rolls(art.level, &art.sub_stats)
+}
+
+/// How one substat of a 5-star artifact was rolled.
+#[derive(Clone, Debug)]
+pub struct RollBreakdown {
+    pub stat: Stats,
+    pub value: f64,
+    /// Value of each roll, including the initial one.
+    pub values: Vec<f64>,
+    /// Tier of each roll, from 1 (lowest, ~70% of max) to 4 (max).
+    pub tiers: Vec<u8>,
+    /// Sum of the rolls as a percentage of one max roll.
+    pub roll_value: f64,
+}
+
+impl RollBreakdown {
+    pub fn rolls(&self) -> usize {
+        self.values.len()
+    }
+}
+
+/// The four possible single-roll values of `stat`, lowest first.
+fn roll_tiers(stat: &Stats) -> Option<Vec<f64>> {
+    let mut tiers = SUBOP_LIST
+        .get(stat)?
+        .values()
+        .filter(|v| v.len() == 1)
+        .map(|v| v[0])
+        .collect::<Vec<_>>();
+    tiers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Some(tiers)
+}
+
+/// Value of the highest single roll of `stat` on a 5-star artifact.
+pub fn max_roll(stat: &Stats) -> Option<f64> {
+    roll_tiers(stat)?.last().copied()
+}
+
+/// Breaks each substat down into its individual rolls using `resolve_op`,
+/// `None` when the rolls cannot be told apart.
+pub fn roll_breakdown(art: &Reliquary) -> Option<Vec<RollBreakdown>> {
+    if art.rarity != 5 {
+        return None;
+    }
+    breakdown(art.level, &art.sub_stats)
+}
+
+fn breakdown(level: u8, sub_stats: &[Option<StatsValue>; 4]) -> Option<Vec<RollBreakdown>> {
+    let ops = resolve_rolls(level, sub_stats)?;
+    let mut breakdown = Vec::with_capacity(4);
+    for (sub, values) in sub_stats.iter().zip(ops) {
+        let Some(sub) = sub else {
+            continue;
+        };
+        let tiers = roll_tiers(&sub.0)?;
+        let max = *tiers.last()?;
+        let tier_of = |v: f64| {
+            tiers
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| (*a - v).abs().partial_cmp(&(*b - v).abs()).unwrap())
+                .map(|(i, _)| i as u8 + 1)
+                .unwrap_or(0)
+        };
+        breakdown.push(RollBreakdown {
+            stat: sub.0,
+            value: sub.1,
+            tiers: values.iter().map(|v| tier_of(*v)).collect(),
+            roll_value: values.iter().sum::<f64>() / max * 100.0,
+            values,
+        });
+    }
+    Some(breakdown)
 }
 
 fn trim(s: f64) -> String {
@@ -117,3 +226,82 @@ fn trim(s: f64) -> String {
     }
     format!("{}.0", s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subs(values: [(Stats, f64); 4]) -> [Option<StatsValue>; 4] {
+        values.map(|(stat, value)| Some(StatsValue(stat, value)))
+    }
+
+    #[test]
+    fn three_liner_has_eight_rolls() {
+        // crit rate 10.9 is 3.1+3.9+3.9 or 2.7*4; only the latter adds up to 8
+        let subs = subs([
+            (Stats::Critical, 10.9),
+            (Stats::CriticalHurt, 7.8),
+            (Stats::AttackPercent, 10.5),
+            (Stats::ChargeEfficiency, 6.5),
+        ]);
+        let rolls = resolve_rolls(20, &subs).unwrap();
+        assert_eq!(rolls[0], vec![2.7, 2.7, 2.7, 2.7]);
+        assert_eq!(rolls.iter().map(Vec::len).sum::<usize>(), 8);
+        let breakdown = breakdown(20, &subs).unwrap();
+        assert_eq!(breakdown[0].tiers, vec![1, 1, 1, 1]);
+        assert_eq!(breakdown[1].tiers, vec![4]);
+        assert_eq!(breakdown[1].roll_value.round(), 100.0);
+    }
+
+    #[test]
+    fn four_liner_has_nine_rolls() {
+        let subs = subs([
+            (Stats::Critical, 7.0),
+            (Stats::CriticalHurt, 24.9),
+            (Stats::AttackPercent, 10.5),
+            (Stats::ChargeEfficiency, 6.5),
+        ]);
+        let rolls = resolve_rolls(20, &subs).unwrap();
+        assert_eq!(rolls[1], vec![5.4, 5.4, 6.2, 7.8]);
+        assert_eq!(rolls.iter().map(Vec::len).sum::<usize>(), 9);
+    }
+
+    #[test]
+    fn ambiguous_split_is_rejected() {
+        // crit damage 21.8 fits both as a 3-liner (3 rolls) and a 4-liner (4 rolls)
+        let subs = subs([
+            (Stats::Critical, 7.8),
+            (Stats::CriticalHurt, 21.8),
+            (Stats::AttackPercent, 10.5),
+            (Stats::ChargeEfficiency, 6.5),
+        ]);
+        assert!(resolve_rolls(20, &subs).is_none());
+    }
+
+    #[test]
+    fn inconsistent_roll_count_is_rejected() {
+        // single rolls only, far fewer than a +20 artifact has
+        let subs = subs([
+            (Stats::Critical, 3.9),
+            (Stats::CriticalHurt, 7.8),
+            (Stats::AttackPercent, 4.7),
+            (Stats::ChargeEfficiency, 6.5),
+        ]);
+        assert!(resolve_rolls(20, &subs).is_none());
+        assert!(resolve_rolls(0, &subs).is_some());
+    }
+
+    #[test]
+    fn empty_slot_stays_empty() {
+        let mut subs = subs([
+            (Stats::Critical, 3.9),
+            (Stats::CriticalHurt, 7.8),
+            (Stats::AttackPercent, 4.7),
+            (Stats::ChargeEfficiency, 6.5),
+        ]);
+        subs[3] = None;
+        let rolls = resolve_rolls(0, &subs).unwrap();
+        assert!(rolls[3].is_empty());
+        assert_eq!(breakdown(0, &subs).unwrap().len(), 3);
+    }
+}
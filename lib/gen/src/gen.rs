@@ -1,5 +1,4 @@
 use std::{
-    collections::HashMap,
     fmt::Display,
     io::{BufWriter, Cursor},
     str::FromStr,
@@ -14,6 +13,10 @@ use enka_api::{
     element::Element,
     icon::IconData,
 };
+use gen_utils::{
+    grades::{grade_set_for, GradeSet, GRADES},
+    weights::{format_weights, parse_weights},
+};
 use image::{
     imageops::{
         self, overlay, resize,
@@ -21,15 +24,18 @@ use image::{
     },
     DynamicImage, ImageOutputFormat, Rgba,
 };
-use imageproc::drawing::{draw_text_mut, text_size};
+use imageproc::drawing::{draw_filled_circle_mut, draw_text_mut, text_size};
 use rusttype::{Font, Scale};
 
 pub use crate::default::get_default;
 use serde::Deserialize;
 
 use crate::{
-    constants::{self, get_clock_image, FONT},
-    dupe, locale,
+    constants::{self, get_clock_image},
+    dupe,
+    layout::{self, Align, Layout, Text},
+    locale,
+    style::{CardInput, CardStyle},
 };
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -74,7 +80,7 @@ impl FromStr for ImageFormat {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
 pub enum ScoreCounter {
     #[default]
     Normal,
@@ -82,6 +88,101 @@ pub enum ScoreCounter {
     Def,
     ElementalMastery,
     ChargeEfficiency,
+    Custom(ScoreWeights),
+}
+
+/// Per-stat weights of a custom score.
+///
+/// Every substat is counted in rolls, i.e. divided by the highest single roll of
+/// a 5-star artifact, so a weight of 1 values one max roll of any stat the same.
+/// Written as `key=weight` pairs separated by commas, e.g. `crit=1,atk%=0.75,er=0.5`.
+/// `crit=w` is shorthand for `cr=w,cd=w`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+pub struct ScoreWeights {
+    pub hp: f64,
+    pub atk: f64,
+    pub def: f64,
+    pub hp_percent: f64,
+    pub atk_percent: f64,
+    pub def_percent: f64,
+    pub crit_rate: f64,
+    pub crit_damage: f64,
+    pub energy_recharge: f64,
+    pub elemental_mastery: f64,
+}
+
+impl ScoreWeights {
+    /// Score of one max roll at weight 1. Matches a max CRIT roll of the presets,
+    /// so custom scores share their grade scale.
+    pub const ROLL_SCORE: f64 = 7.8;
+    const KEYS: [&'static str; 10] = [
+        "hp", "atk", "def", "hp%", "atk%", "def%", "cr", "cd", "er", "em",
+    ];
+
+    fn to_array(self) -> [f64; 10] {
+        [
+            self.hp,
+            self.atk,
+            self.def,
+            self.hp_percent,
+            self.atk_percent,
+            self.def_percent,
+            self.crit_rate,
+            self.crit_damage,
+            self.energy_recharge,
+            self.elemental_mastery,
+        ]
+    }
+    pub fn get(&self, stat: &Stats) -> f64 {
+        match stat {
+            Stats::Hp => self.hp,
+            Stats::Attack => self.atk,
+            Stats::Defense => self.def,
+            Stats::HpPercent => self.hp_percent,
+            Stats::AttackPercent => self.atk_percent,
+            Stats::DefensePercent => self.def_percent,
+            Stats::Critical => self.crit_rate,
+            Stats::CriticalHurt => self.crit_damage,
+            Stats::ChargeEfficiency => self.energy_recharge,
+            Stats::ElementMastery => self.elemental_mastery,
+            _ => 0.0,
+        }
+    }
+    /// Score of one substat, `None` when it is not weighted.
+    pub fn score(&self, StatsValue(stat, value): &StatsValue) -> Option<f64> {
+        let weight = self.get(stat);
+        if weight <= 0.0 {
+            return None;
+        }
+        Some(value / dupe::max_roll(stat)? * weight * Self::ROLL_SCORE)
+    }
+}
+
+impl Display for ScoreWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_weights(&Self::KEYS, &self.to_array()))
+    }
+}
+
+impl FromStr for ScoreWeights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [hp, atk, def, hp_percent, atk_percent, def_percent, crit_rate, crit_damage, energy_recharge, elemental_mastery] =
+            parse_weights(s, &Self::KEYS)?;
+        Ok(Self {
+            hp,
+            atk,
+            def,
+            hp_percent,
+            atk_percent,
+            def_percent,
+            crit_rate,
+            crit_damage,
+            energy_recharge,
+            elemental_mastery,
+        })
+    }
 }
 
 impl ScoreCounter {
@@ -92,6 +193,7 @@ impl ScoreCounter {
             ScoreCounter::Def => "Def",
             ScoreCounter::ElementalMastery => "Mastery",
             ScoreCounter::ChargeEfficiency => "Charge",
+            ScoreCounter::Custom(_) => "Custom",
         }
     }
     fn ja(&self) -> &str {
@@ -101,6 +203,7 @@ impl ScoreCounter {
             ScoreCounter::Def => "防御型",
             ScoreCounter::ElementalMastery => "熟知型",
             ScoreCounter::ChargeEfficiency => "チャージ型",
+            ScoreCounter::Custom(_) => "カスタム",
         }
     }
     pub fn to_string_locale(&self, lang: &str) -> String {
@@ -109,11 +212,30 @@ impl ScoreCounter {
             _ => self.en().to_string(),
         }
     }
+    /// Lowercase name accepted by `from_str`, also the key of the counter's grade
+    /// set. Custom weights use the `custom` set.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ScoreCounter::Normal => "normal",
+            ScoreCounter::Hp => "hp",
+            ScoreCounter::Def => "def",
+            ScoreCounter::ElementalMastery => "elementalmastery",
+            ScoreCounter::ChargeEfficiency => "chargeefficiency",
+            ScoreCounter::Custom(_) => "custom",
+        }
+    }
+    /// Grade thresholds used for this counter unless the caller picks a set.
+    pub fn grades(&self) -> &'static GradeSet {
+        grade_set_for(&GRADES.genshin, self.key())
+    }
 }
 
 impl Display for ScoreCounter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.en())
+        match self {
+            ScoreCounter::Custom(weights) => write!(f, "{}", weights),
+            _ => write!(f, "{}", self.en()),
+        }
     }
 }
 
@@ -136,6 +258,7 @@ impl FromStr for ScoreCounter {
             "mastery" => Ok(ScoreCounter::ElementalMastery),
             "chargeefficiency" => Ok(ScoreCounter::ChargeEfficiency),
             "charge" => Ok(ScoreCounter::ChargeEfficiency),
+            s if s.contains('=') => Ok(ScoreCounter::Custom(s.parse()?)),
             _ => Err(format!("{} is not ScoreCounter", s)),
         }
     }
@@ -170,6 +293,76 @@ impl From<&str> for Lang {
     }
 }
 
+/// Optional settings of the card.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CardOptions {
+    /// Marks every substat roll with a dot colored by its tier.
+    pub roll_markers: bool,
+    /// Metric shown in the total score panel.
+    pub total: TotalMetric,
+    /// Grade thresholds, `None` for the counter's own set.
+    pub grades: Option<&'static GradeSet>,
+    /// Card layout of the classic style, `None` for the built-in one.
+    pub layout: Option<&'static Layout>,
+    pub style: CardStyle,
+}
+
+/// What the total score panel of the card shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TotalMetric {
+    #[default]
+    Score,
+    CritValue,
+    RollValue,
+}
+
+impl FromStr for TotalMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "score" => Ok(TotalMetric::Score),
+            "cv" | "critvalue" => Ok(TotalMetric::CritValue),
+            "rv" | "rollvalue" => Ok(TotalMetric::RollValue),
+            _ => Err(format!("{} is not TotalMetric", s)),
+        }
+    }
+}
+
+/// Crit Value and Roll Value of the equipped artifacts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArtifactMetrics {
+    /// Crit Value summed over all artifacts.
+    pub crit_value: f64,
+    /// Substat rolls of the 5-star artifacts as a percentage of the most rolls
+    /// they could have at their levels, 0 without any 5-star artifact.
+    pub roll_value: f64,
+}
+
+impl ArtifactMetrics {
+    pub fn of(data: &Character) -> Self {
+        let artifacts = data.reliquarys();
+        let (rolls, max) = artifacts
+            .iter()
+            .filter_map(|a| Some((substat_rolls(a.rarity, &a.sub_stats)?, max_rolls(a.level))))
+            .fold((0.0, 0.0), |(rolls, max), (r, m)| {
+                (rolls + r, max + m as f64)
+            });
+        Self {
+            crit_value: artifacts.iter().map(get_crit_value).sum(),
+            roll_value: if max > 0.0 { rolls / max * 100.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Marker colors for roll tiers 1 (low) to 4 (max).
+const ROLL_TIER_COLORS: [Rgba<u8>; 4] = [
+    Rgba([170, 170, 170, 255]),
+    Rgba([100, 180, 255, 255]),
+    Rgba([200, 120, 255, 255]),
+    Rgba([255, 200, 60, 255]),
+];
+
 pub async fn generate(
     data: Character,
     api: &Api,
@@ -177,104 +370,140 @@ pub async fn generate(
     icons: &IconData,
     counter: ScoreCounter,
     format: ImageFormat,
+    options: CardOptions,
 ) -> Option<Vec<u8>> {
+    let input = CardInput {
+        data: &data,
+        api,
+        lang: raw_lang,
+        icons,
+        counter,
+        options,
+    };
+    let image = options.style.renderer().render(input).await?;
+    convert(image, format)
+}
+
+/// The original landscape card, drawn as described by the layout in `options`.
+pub(crate) async fn render_classic(input: CardInput<'_>) -> Option<DynamicImage> {
+    let CardInput {
+        data,
+        api,
+        lang: raw_lang,
+        icons,
+        counter,
+        options,
+    } = input;
     let lang = &raw_lang.to_string();
-    let font = &FONT;
+    let layout = options.layout.unwrap_or(&layout::BUILTIN);
     let mut image = constants::get_base_image(&data.element)?;
     let character_image = data.image_gacha_splash(api).await?;
-    match data.id.0 {
-        // liuyun
-        10000093 => {
-            let character_image = character_image.resize_exact(800, 600, Nearest);
-            imageops::overlay(&mut image, &character_image, 0, 50);
-        }
-        // main characters
-        10000005 | 10000007 => {
-            let character_image = character_image.resize_exact(600, 600, Nearest);
-            imageops::overlay(&mut image, &character_image, 150, 50);
-        }
-        _ => {
-            let character_image = character_image.resize_exact(1200, 600, Nearest);
-            imageops::overlay(&mut image, &character_image, -225, 50);
-        }
-    };
+    let splash = layout.splash(data.id.0);
+    let character_image = character_image.resize_exact(splash.width, splash.height, Nearest);
+    imageops::overlay(
+        &mut image,
+        &character_image,
+        splash.x.into(),
+        splash.y.into(),
+    );
     let character_name = data.name(api, lang).ok()?;
     let character_level = format!("Lv.{},{}", data.level, data.friendship());
-    let white = image::Rgba([255, 255, 255, 255]);
-    draw_text_mut(
+    draw(
         &mut image,
-        white,
-        30,
-        20,
-        Scale::uniform(60.0),
-        font,
-        character_name,
+        layout,
+        &layout.name,
+        0,
+        0,
+        None,
+        &character_name,
     );
-    draw_text_mut(
+    draw(
         &mut image,
-        white,
-        35,
-        80,
-        Scale::uniform(32.0),
-        font,
+        layout,
+        &layout.level,
+        0,
+        0,
+        None,
         &character_level,
     );
 
-    let scale = Scale::uniform(25.0);
+    let skills = &layout.skills;
     for (index, skill) in data.skills().iter().enumerate() {
+        let dy = index as i32 * skills.step;
         let img = skill.image(api).await.ok()?;
-        let img = img.resize(80, 80, Triangle);
-        overlay(&mut image, &img, 20, 330 + index as i64 * 100);
+        let img = img.resize(skills.icon.width, skills.icon.height, Triangle);
+        overlay(
+            &mut image,
+            &img,
+            skills.icon.x.into(),
+            (skills.icon.y + dy).into(),
+        );
         let lv = skill.level() + skill.extra_level();
-        let color = if lv > 9 {
-            image::Rgba([0, 255, 255, 255])
-        } else {
-            white
-        };
-        draw_text_mut(
+        let color = (lv > 9).then_some(Rgba(skills.boosted_color));
+        draw(
             &mut image,
+            layout,
+            &skills.level,
+            0,
+            dy,
             color,
-            40,
-            400 + index as i32 * 100,
-            scale,
-            font,
             &format!("Lv.{}", lv),
         );
     }
 
     let clocks = data.talents();
+    let frame = layout.constellations.frame;
+    let icon = layout.constellations.icon;
     for (index, clock) in clocks.iter().enumerate() {
         let locked_image = get_clock_image(data.element.fight_prop_name(), !clock.is_unlock())?;
-        let mut locked_image = resize(locked_image, 70, 70, Triangle);
+        let mut locked_image = resize(locked_image, frame.width, frame.height, Triangle);
         if clock.is_unlock() {
             let img = clock.image(api).await.ok()?;
-            let img = resize(&img, 35, 35, Triangle);
-            overlay(&mut locked_image, &img, 16, 15);
+            let img = resize(&img, icon.width, icon.height, Triangle);
+            overlay(&mut locked_image, &img, icon.x.into(), icon.y.into());
         }
-        overlay(&mut image, &locked_image, 680, 100 + 80 * index as i64);
+        let y = frame.y + layout.constellations.step * index as i32;
+        overlay(&mut image, &locked_image, frame.x.into(), y.into());
     }
 
     let weapon = data.weapon();
+    let wl = &layout.weapon;
     let weapon_img = weapon.image_icon(api).await.ok()?;
-    let weapon_img = weapon_img.resize_exact(129, 128, Triangle);
-    overlay(&mut image, &weapon_img, 1430, 50);
+    let weapon_img = weapon_img.resize_exact(wl.icon.width, wl.icon.height, Triangle);
+    overlay(&mut image, &weapon_img, wl.icon.x.into(), wl.icon.y.into());
     let weapon_rarity_img = get_rarity_image(weapon.rarity)?;
-    overlay(&mut image, &weapon_rarity_img, 1422, 173);
+    overlay(
+        &mut image,
+        &weapon_rarity_img,
+        wl.rarity.x.into(),
+        wl.rarity.y.into(),
+    );
     let ascension = format!("R{}", weapon.refinement + 1);
-    draw_text_mut(&mut image, white, 1435, 45, scale, font, &ascension);
+    draw(&mut image, layout, &wl.refinement, 0, 0, None, &ascension);
     let weapon_level = format!("Lv.{}", weapon.level);
     let weapon_name = weapon.name(api, lang)?;
-    let scale = Scale::uniform(30.0);
-    draw_text_resized(&mut image, white, 1600, 45, scale, font, weapon_name, 250);
-    draw_text_mut(&mut image, white, 1600, 85, scale, font, &weapon_level);
-    let scale = Scale::uniform(25.0);
+    draw(&mut image, layout, &wl.name, 0, 0, None, &weapon_name);
+    draw(&mut image, layout, &wl.level, 0, 0, None, &weapon_level);
     let weapon_damage = format!("ATK:{}", weapon.base_attack);
     let mut damage_image = icons.image("FIGHT_PROP_ATTACK.svg", 1.8)?;
     for p in damage_image.pixels_mut() {
         p.0 = [255, 255, 255, p.0[3]];
     }
-    draw_text_mut(&mut image, white, 1630, 125, scale, font, &weapon_damage);
-    overlay(&mut image, &damage_image, 1600, 125);
+    draw(
+        &mut image,
+        layout,
+        &wl.base_attack,
+        0,
+        0,
+        None,
+        &weapon_damage,
+    );
+    overlay(
+        &mut image,
+        &damage_image,
+        wl.base_attack_icon.x.into(),
+        wl.base_attack_icon.y.into(),
+    );
     if let Some(stats) = weapon.stats {
         let weapon_sub = format!(
             "{} {}{}",
@@ -286,23 +515,29 @@ pub async fn generate(
         for p in weapon_sub_image.pixels_mut() {
             p.0 = [255, 255, 255, p.0[3]];
         }
-        draw_text_mut(&mut image, white, 1630, 160, scale, font, &weapon_sub);
-        overlay(&mut image, &weapon_sub_image, 1600, 160);
+        draw(&mut image, layout, &wl.sub_stat, 0, 0, None, &weapon_sub);
+        overlay(
+            &mut image,
+            &weapon_sub_image,
+            wl.sub_stat_icon.x.into(),
+            wl.sub_stat_icon.y.into(),
+        );
     }
     let artifacts = data.reliquarys();
-    let mut artifact_x = 30;
+    let al = &layout.artifacts;
+    let sl = &al.substats;
+    let mut artifact_x = al.x;
+    let metrics = ArtifactMetrics::of(data);
+    let grades = options.grades.unwrap_or_else(|| counter.grades());
     let mut artifact_scores = 0.0;
     for artifact in get_artifacts(artifacts) {
         if artifact.is_none() {
-            artifact_x += 373;
+            artifact_x += al.step;
             continue;
         }
         let artifact = artifact.unwrap();
-        let gray = image::Rgba([240, 240, 240, 200]);
-        if let Some(o) = dupe::resolve_op(artifact) {
-            let mut sub_y = 785;
-            let o = o
-                .iter()
+        let rolls = dupe::resolve_op(artifact).map(|o| {
+            o.iter()
                 .enumerate()
                 .map(|(i, x)| {
                     if let Some(s) = artifact.sub_stats[i] {
@@ -320,40 +555,29 @@ pub async fn generate(
                         "".to_string()
                     }
                 })
-                .collect::<Vec<_>>();
-            let scale = Scale::uniform(15.0);
-            for x in o {
-                let width = text_size(scale, font, &x).0;
-                sub_y += 52;
-                draw_text_mut(
-                    &mut image,
-                    gray,
-                    artifact_x as i32 + 340 - width,
-                    sub_y,
-                    scale,
-                    font,
-                    &x,
-                );
+                .collect::<Vec<_>>()
+        });
+        if let Some(rolls) = rolls {
+            for (i, x) in rolls.iter().enumerate() {
+                let dy = sl.y + sl.step * i as i32;
+                draw(&mut image, layout, &sl.rolls, artifact_x, dy, None, x);
             }
-        };
+        }
         let (score, used) = get_score(artifact, &counter);
         artifact_scores += score;
-        let rank_img = constants::get_grade_image(score, Some(artifact.position))?;
-        overlay(&mut image, &rank_img, artifact_x + 50, 1015);
-        let score = round_to_1_decimal_places(score);
-        let scale = Scale::uniform(40.0);
-        let score_width = text_size(scale, font, &score).0;
-        draw_text_mut(
+        let rank_img = constants::get_grade_image(score, Some(artifact.position), grades)?;
+        overlay(
             &mut image,
-            white,
-            artifact_x as i32 - score_width + 350,
-            1015,
-            scale,
-            font,
-            &score,
+            &rank_img,
+            (artifact_x + al.grade.x).into(),
+            al.grade.y.into(),
         );
+        let score = round_to_1_decimal_places(score);
+        draw(&mut image, layout, &al.score, artifact_x, 0, None, &score);
         let img = artifact.image_icon(api).await.ok()?;
-        let mut img = img.resize_exact(256, 256, Triangle).into_rgba8();
+        let mut img = img
+            .resize_exact(al.icon.width, al.icon.height, Triangle)
+            .into_rgba8();
         img.pixels_mut().for_each(|p| {
             let p3 = p.0[3];
             if p3 > 100 {
@@ -362,7 +586,12 @@ pub async fn generate(
                 p.0[3] -= 20;
             }
         });
-        overlay(&mut image, &img, artifact_x, 630);
+        overlay(
+            &mut image,
+            &img,
+            (artifact_x + al.icon.x).into(),
+            al.icon.y.into(),
+        );
         let main = artifact.main_stats;
         let main_type = main.0.name(api, lang)?;
         let main_value = if is_percent(&main.0) {
@@ -374,58 +603,72 @@ pub async fn generate(
         for p in main_image.pixels_mut() {
             p.0 = [255, 255, 255, p.0[3]];
         }
-        let scale = Scale::uniform(30.0);
-        let main_type_width = text_size(scale, font, main_type).0;
-        draw_text_mut(
+        let main_type_width = text_size(
+            Scale::uniform(al.main_type.size),
+            layout.font(&al.main_type),
+            &main_type,
+        )
+        .0;
+        draw(
             &mut image,
-            white,
-            artifact_x as i32 + 340 - main_type_width,
-            660,
-            scale,
-            font,
-            main_type,
+            layout,
+            &al.main_type,
+            artifact_x,
+            0,
+            None,
+            &main_type,
         );
-        let scale = Scale::uniform(60.0);
-        let main_value_width = text_size(scale, font, &main_value).0;
-        draw_text_mut(
+        draw(
             &mut image,
-            white,
-            artifact_x as i32 + 340 - main_value_width,
-            690,
-            scale,
-            font,
+            layout,
+            &al.main_value,
+            artifact_x,
+            0,
+            None,
             &main_value,
         );
         overlay(
             &mut image,
             &main_image,
-            artifact_x + 310 - main_type_width as i64,
-            660,
+            (artifact_x + al.main_type.x - al.main_icon_gap - main_type_width).into(),
+            al.main_type.y.into(),
         );
         let level = format!("+{}", artifact.level);
-        let scale = Scale::uniform(25.0);
-        let level_width = text_size(scale, font, &level).0;
-        draw_text_mut(
-            &mut image,
-            white,
-            artifact_x as i32 + 340 - level_width,
-            750,
-            scale,
-            font,
-            &level,
-        );
-        let mut sub_y = 810;
-        let gray = Rgba([240, 240, 240, 200]);
+        draw(&mut image, layout, &al.level, artifact_x, 0, None, &level);
+        let mut sub_y = sl.y;
+        let dim = Rgba(sl.dim_color);
+        // only drawn when every substat could be split into its rolls
+        let breakdown = options
+            .roll_markers
+            .then(|| dupe::roll_breakdown(artifact))
+            .flatten()
+            .unwrap_or_default();
         for (_, sub) in artifact.sub_stats.iter().enumerate() {
             if sub.is_none() {
-                sub_y += 40;
+                sub_y += sl.empty_step;
                 continue;
             }
             let sub = sub.unwrap();
+            let rolls = breakdown
+                .iter()
+                .find(|b| b.stat.id() == sub.0.id())
+                .filter(|b| b.tiers.iter().all(|t| (1..=4).contains(t)));
+            if let Some(b) = rolls {
+                let m = &sl.markers;
+                for (i, tier) in b.tiers.iter().enumerate() {
+                    let color = ROLL_TIER_COLORS[*tier as usize - 1];
+                    draw_filled_circle_mut(
+                        &mut image,
+                        (artifact_x + m.x + i as i32 * m.spacing, sub_y + m.y),
+                        m.radius,
+                        color,
+                    );
+                }
+            }
             let color = if used.contains(&sub.0.id().to_string()) {
-                white
+                None
             } else {
-                gray
+                Some(dim)
             };
             let sub_type = sub.0.name(api, lang)?;
             let sub_value = if is_percent(&sub.0) {
@@ -437,69 +680,64 @@ pub async fn generate(
             for p in sub_image.pixels_mut() {
                 p.0 = [255, 255, 255, p.0[3]];
             }
-            let scale = Scale::uniform(30.0);
-            let sub_type_width = text_size(scale, font, sub_type).0;
-            if sub_type_width <= 200 {
-                draw_text_mut(
-                    &mut image,
-                    color,
-                    artifact_x as i32 + 60,
-                    sub_y,
-                    scale,
-                    font,
-                    sub_type,
-                );
+            let sub_type_width = text_size(
+                Scale::uniform(sl.name.size),
+                layout.font(&sl.name),
+                &sub_type,
+            )
+            .0;
+            let name = if sub_type_width <= sl.long_name_width {
+                &sl.name
             } else {
-                draw_text_mut(
-                    &mut image,
-                    color,
-                    artifact_x as i32 + 60,
-                    sub_y + 7,
-                    Scale::uniform(20.0),
-                    font,
-                    sub_type,
-                );
-            }
-            let sub_value_width = text_size(scale, font, &sub_value).0;
-            draw_text_mut(
+                &sl.long_name
+            };
+            draw(
+                &mut image, layout, name, artifact_x, sub_y, color, &sub_type,
+            );
+            draw(
+                &mut image, layout, &sl.value, artifact_x, sub_y, color, &sub_value,
+            );
+            overlay(
                 &mut image,
-                color,
-                artifact_x as i32 + 340 - sub_value_width,
-                sub_y,
-                scale,
-                font,
-                &sub_value,
+                &sub_image,
+                (artifact_x + sl.icon_x).into(),
+                sub_y.into(),
             );
-            overlay(&mut image, &sub_image, artifact_x + 20, sub_y.into());
-            sub_y += 52;
+            sub_y += sl.step;
         }
-        artifact_x += 373;
-    }
-    let rank_img = constants::get_grade_image(artifact_scores, None)?;
-    overlay(&mut image, &rank_img, 1810, 355);
-    let total_score = locale::Locale::from(locale::json!({
-        "en": "Total Score",
-        "ja": "総合スコア",
-    }))
-    .get(raw_lang)
-    .to_string();
-    let scale = Scale::uniform(30.0);
-    draw_text_mut(&mut image, white, 1440, 350, scale, font, &total_score);
-    let text = round_to_1_decimal_places(artifact_scores).to_string();
-    let scale = Scale::uniform(90.0);
-    let (text_w, text_h) = text_size(scale, font, &text);
-    draw_text_mut(
-        &mut image,
-        white,
-        1630 - text_w / 2,
-        450 - text_h / 2,
-        scale,
-        font,
-        &text,
-    );
+        artifact_x += al.step;
+    }
+    let tl = &layout.total;
+    let rank_img = constants::get_grade_image(artifact_scores, None, grades)?;
+    overlay(&mut image, &rank_img, tl.grade.x.into(), tl.grade.y.into());
+    let (total_label, text) = match options.total {
+        TotalMetric::Score => (
+            locale::json!({
+                "en": "Total Score",
+                "ja": "総合スコア",
+            }),
+            round_to_1_decimal_places(artifact_scores),
+        ),
+        TotalMetric::CritValue => (
+            locale::json!({
+                "en": "Crit Value",
+                "ja": "会心値",
+            }),
+            round_to_1_decimal_places(metrics.crit_value),
+        ),
+        TotalMetric::RollValue => (
+            locale::json!({
+                "en": "Roll Value",
+                "ja": "ロール値",
+            }),
+            format!("{}%", metrics.roll_value.round()),
+        ),
+    };
+    let total_score = locale::Locale::from(total_label).get(raw_lang).to_string();
+    draw(&mut image, layout, &tl.label, 0, 0, None, &total_score);
+    draw(&mut image, layout, &tl.value, 0, 0, None, &text);
 
     let default_status = data.fight_prop();
-    let mut status_y = 65;
     let statuslist = vec![
         (
             (default_status.display_max_hp.round() as u32).to_string(),
@@ -553,132 +791,75 @@ pub async fn generate(
             Stats::ElementAddHurt(data.element).name(api, lang)?,
         ),
     ];
-    let scale = Scale::uniform(35.0);
+    let stl = &layout.stats;
+    let mut status_y = 0;
     for (status, code) in statuslist {
-        let status_width = text_size(scale, font, &status).0;
-        draw_text_mut(
-            &mut image,
-            white,
-            1350 - status_width,
-            status_y,
-            scale,
-            font,
-            &status,
-        );
-        draw_text_mut(&mut image, white, 845, status_y, scale, font, code);
-        status_y += 70;
+        draw(&mut image, layout, &stl.value, 0, status_y, None, &status);
+        draw(&mut image, layout, &stl.label, 0, status_y, None, &code);
+        status_y += stl.step;
     }
-    status_y -= 70;
+    status_y -= stl.step;
     let img = data.element.image(icons, 2.5)?;
-    overlay(&mut image, &img, 790, status_y.into());
+    overlay(
+        &mut image,
+        &img,
+        stl.element_icon_x.into(),
+        (stl.label.y + status_y).into(),
+    );
 
-    let sets = data
-        .reliquarys()
-        .iter()
-        .map(|x| x.set_name(api, lang))
-        .collect::<Vec<Option<&str>>>();
-    let mut set = HashMap::<String, u32>::new();
-    for s in sets.into_iter().flatten() {
-        if set.contains_key(s) {
-            let count = set.get(s).unwrap();
-            set.insert(s.to_string(), count + 1);
-        } else {
-            set.insert(s.to_string(), 1);
-        }
-    }
-    let mut largest_set_key = 0;
-    let mut largest_set: Option<(&String, &u32)> = None;
-    let mut second_largest_set: Option<(&String, &u32)> = None;
-    for (key, value) in set.iter() {
-        if value >= &largest_set_key {
-            second_largest_set = largest_set;
-            largest_set = Some((key, value));
-            largest_set_key = *value;
-        }
-    }
-    let (set_name, set_count) = if let Some(largest_set) = largest_set {
-        if largest_set.1 > &1 {
-            (largest_set.0.to_string(), *largest_set.1)
-        } else {
-            ("None".to_string(), 0)
-        }
+    let setl = &layout.sets;
+    let sets = data.artifact_sets();
+    let set_y = if sets.len() > 1 {
+        setl.two_set_y - setl.name.y
     } else {
-        ("None".to_string(), 0)
+        0
     };
-    let (second_set_name, second_set_count) = if let Some(second_largest_set) = second_largest_set {
-        if second_largest_set.1 > &1 {
-            (
-                Some(second_largest_set.0.to_string()),
-                *second_largest_set.1,
-            )
-        } else {
-            (None, 0)
-        }
-    } else {
-        (None, 0)
-    };
-    let first_key_color = if largest_set_key > 3 {
-        Rgba([0, 255, 255, 255])
-    } else {
-        white
-    };
-    if let Some(second_set_name) = second_set_name {
-        draw_text_mut(
-            &mut image,
-            white,
-            1820,
-            240,
-            scale,
-            font,
-            &format!("{}", set_count),
-        );
-        draw_text_mut(
-            &mut image,
-            white,
-            1820,
-            285,
-            scale,
-            font,
-            &format!("{}", second_set_count),
-        );
-        draw_text_resized(&mut image, white, 1520, 240, scale, font, &set_name, 250);
-        draw_text_resized(
-            &mut image,
-            white,
-            1520,
-            285,
-            scale,
-            font,
-            &second_set_name,
-            250,
-        );
-    } else {
-        draw_text_resized(
-            &mut image,
-            first_key_color,
-            1520,
-            260,
-            scale,
-            font,
-            &set_name,
-            250,
-        );
-        draw_text_mut(
-            &mut image,
-            white,
-            1820,
-            260,
-            scale,
-            font,
-            &format!("{}", set_count),
-        );
+    for (index, set) in sets.iter().take(2).enumerate() {
+        let dy = set_y + setl.step * index as i32;
+        let color = (set.count > 3).then_some(Rgba(setl.full_color));
+        let name = set.name(api, lang).unwrap_or_default();
+        draw(&mut image, layout, &setl.name, 0, dy, color, &name);
+        let count = format!("{}", set.count);
+        draw(&mut image, layout, &setl.count, 0, dy, color, &count);
+    }
+    if sets.is_empty() {
+        draw(&mut image, layout, &setl.name, 0, 0, None, "None");
+        draw(&mut image, layout, &setl.count, 0, 0, None, "0");
     }
 
     let kind = counter.to_string_locale(lang);
-    let scale = Scale::uniform(35.0);
-    let (kind_w, _) = text_size(scale, font, &kind);
-    draw_text_mut(&mut image, white, 1870 - kind_w, 580, scale, font, &kind);
-    convert(image, format)
+    draw(&mut image, layout, &layout.counter, 0, 0, None, &kind);
+    Some(image)
+}
+
+/// Draws `s` as described by `text`, moved by `dx`/`dy`. `color` overrides the text color.
+fn draw(
+    image: &mut DynamicImage,
+    layout: &Layout,
+    text: &Text,
+    dx: i32,
+    dy: i32,
+    color: Option<Rgba<u8>>,
+    s: &str,
+) {
+    let font = layout.font(text);
+    let color = color.unwrap_or_else(|| text.rgba());
+    let scale = Scale::uniform(text.size);
+    let (x, y) = (text.x + dx, text.y + dy);
+    match text.align {
+        Align::Left => match text.max_width {
+            Some(max_width) => draw_text_resized(image, color, x, y, scale, font, s, max_width),
+            None => draw_text_mut(image, color, x, y, scale, font, s),
+        },
+        Align::Right => {
+            let (w, _) = text_size(scale, font, s);
+            draw_text_mut(image, color, x - w, y, scale, font, s);
+        }
+        Align::Center => {
+            let (w, h) = text_size(scale, font, s);
+            draw_text_mut(image, color, x - w / 2, y - h / 2, scale, font, s);
+        }
+    }
 }
 
 pub fn convert(image: DynamicImage, format: ImageFormat) -> Option<Vec<u8>> {
@@ -712,7 +893,7 @@ pub fn is_percent(stat: &Stats) -> bool {
     )
 }
 
-fn round_to_1_decimal_places(x: f64) -> String {
+pub(crate) fn round_to_1_decimal_places(x: f64) -> String {
     let s = ((x * 10.0).round() / 10.0).to_string();
     if !s.contains('.') {
         return format!("{}.0", s);
@@ -723,6 +904,15 @@ fn round_to_1_decimal_places(x: f64) -> String {
 pub fn mini_score(data: [Option<StatsValue>; 4], counter: &ScoreCounter) -> (f64, Vec<String>) {
     let mut score = 0.0;
     let mut used: Vec<Stats> = Vec::new();
+    if let ScoreCounter::Custom(weights) = counter {
+        for sub in data.into_iter().flatten() {
+            if let Some(s) = weights.score(&sub) {
+                score += s;
+                used.push(sub.0);
+            }
+        }
+        return (score, used.iter().map(|x| x.id().to_string()).collect());
+    }
     for sub in data {
         if sub.is_none() {
             continue;
@@ -779,7 +969,47 @@ pub fn get_score(data: &Reliquary, counter: &ScoreCounter) -> (f64, Vec<String>)
     mini_score(data.sub_stats, counter)
 }
 
-fn draw_text_resized(
+/// Crit Value of the substats: crit rate counted twice plus crit damage.
+pub fn get_crit_value(data: &Reliquary) -> f64 {
+    data.sub_stats
+        .iter()
+        .flatten()
+        .map(|StatsValue(stat, value)| match stat {
+            Stats::Critical => value * 2.0,
+            Stats::CriticalHurt => *value,
+            _ => 0.0,
+        })
+        .sum()
+}
+
+/// Most substat rolls a 5-star artifact can have at `level`: four initial
+/// substats plus one upgrade every 4 levels.
+pub fn max_rolls(level: u8) -> u8 {
+    4 + level.min(20) / 4
+}
+
+/// Substats of a 5-star artifact counted in max rolls. `None` for other
+/// rarities, whose rolls are smaller.
+fn substat_rolls(rarity: u8, sub_stats: &[Option<StatsValue>; 4]) -> Option<f64> {
+    if rarity != 5 {
+        return None;
+    }
+    Some(
+        sub_stats
+            .iter()
+            .flatten()
+            .filter_map(|StatsValue(stat, value)| Some(value / dupe::max_roll(stat)?))
+            .sum(),
+    )
+}
+
+/// Roll Value of a 5-star artifact: its substats, counted in max rolls, as a
+/// percentage of `max_rolls` at its level. `None` for other rarities.
+pub fn get_roll_value(data: &Reliquary) -> Option<f64> {
+    Some(substat_rolls(data.rarity, &data.sub_stats)? / max_rolls(data.level) as f64 * 100.0)
+}
+
+pub(crate) fn draw_text_resized(
     canvas: &mut DynamicImage,
     color: image::Rgba<u8>,
     x: i32,
@@ -801,7 +1031,7 @@ fn draw_text_resized(
     draw_text_mut(canvas, color, x, y, scale, font, text)
 }
 
-fn get_artifacts(artifacts: &Vec<Reliquary>) -> Vec<Option<&Reliquary>> {
+pub(crate) fn get_artifacts(artifacts: &Vec<Reliquary>) -> Vec<Option<&Reliquary>> {
     let mut result = vec![None; 5];
     for artifact in artifacts {
         let position = artifact.position;
@@ -815,3 +1045,110 @@ fn get_artifacts(artifacts: &Vec<Reliquary>) -> Vec<Option<&Reliquary>> {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolled(stat: Stats, rolls: f64) -> Option<StatsValue> {
+        Some(StatsValue(stat, dupe::max_roll(&stat).unwrap() * rolls))
+    }
+
+    #[test]
+    fn roll_value_counts_against_the_level() {
+        let subs = [
+            rolled(Stats::Critical, 3.0),
+            rolled(Stats::CriticalHurt, 2.0),
+            rolled(Stats::AttackPercent, 2.0),
+            rolled(Stats::ChargeEfficiency, 2.0),
+        ];
+        let rv = substat_rolls(5, &subs).unwrap() / max_rolls(20) as f64 * 100.0;
+        assert!((rv - 100.0).abs() < 1e-9);
+        let subs = [
+            rolled(Stats::Critical, 1.0),
+            rolled(Stats::CriticalHurt, 1.0),
+            rolled(Stats::AttackPercent, 1.0),
+            None,
+        ];
+        let rv = substat_rolls(5, &subs).unwrap() / max_rolls(0) as f64 * 100.0;
+        assert!((rv - 75.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn roll_value_skips_lower_rarities() {
+        let subs = [rolled(Stats::Critical, 1.0), None, None, None];
+        assert_eq!(substat_rolls(4, &subs), None);
+        assert_eq!(max_rolls(16), 8);
+        assert_eq!(max_rolls(20), 9);
+    }
+
+    #[test]
+    fn presets_use_their_grade_sets() {
+        let custom = ScoreCounter::Custom(ScoreWeights::from_str("crit=1").unwrap());
+        let presets = [
+            (ScoreCounter::Normal, "default"),
+            (ScoreCounter::Hp, "default"),
+            (ScoreCounter::Def, "def"),
+            (ScoreCounter::ElementalMastery, "default"),
+            (ScoreCounter::ChargeEfficiency, "chargeefficiency"),
+            (custom, "custom"),
+        ];
+        for (counter, set) in &presets {
+            assert!(
+                std::ptr::eq(counter.grades(), &GRADES.genshin[*set]),
+                "{} should use {}",
+                counter.key(),
+                set
+            );
+            if !matches!(counter, ScoreCounter::Custom(_)) {
+                assert_eq!(ScoreCounter::from_str(counter.key()).as_ref(), Ok(counter));
+            }
+        }
+        for set in GRADES.genshin.keys() {
+            assert!(
+                set == "default" || presets.iter().any(|(c, _)| c.key() == set),
+                "{} is not used by any counter",
+                set
+            );
+        }
+    }
+
+    #[test]
+    fn custom_weights_round_trip() {
+        let weights = ScoreWeights::from_str("crit=1,atk%=0.75,ER=0.5").unwrap();
+        assert_eq!(weights.crit_rate, 1.0);
+        assert_eq!(weights.crit_damage, 1.0);
+        assert_eq!(weights.atk_percent, 0.75);
+        assert_eq!(weights.energy_recharge, 0.5);
+        assert_eq!(weights.to_string(), "atk%=0.75,cr=1,cd=1,er=0.5");
+        assert_eq!(weights.to_string().parse(), Ok(weights));
+        assert_eq!(
+            ScoreCounter::from_str("crit=1,atk%=0.75,er=0.5"),
+            Ok(ScoreCounter::Custom(weights))
+        );
+    }
+
+    #[test]
+    fn custom_weights_reject_unknown_stats() {
+        assert!(ScoreWeights::from_str("spd=1").is_err());
+        assert!(ScoreWeights::from_str("cr=0").is_err());
+        assert!(ScoreCounter::from_str("crit").is_err());
+    }
+
+    #[test]
+    fn custom_score_counts_rolls() {
+        let weights = ScoreWeights::from_str("crit=1,em=0.5").unwrap();
+        let max = |stat| StatsValue(stat, dupe::max_roll(&stat).unwrap());
+        let (score, used) = mini_score(
+            [
+                Some(max(Stats::Critical)),
+                Some(max(Stats::CriticalHurt)),
+                Some(max(Stats::ElementMastery)),
+                Some(max(Stats::Attack)),
+            ],
+            &ScoreCounter::Custom(weights),
+        );
+        assert!((score - ScoreWeights::ROLL_SCORE * 2.5).abs() < 1e-9);
+        assert_eq!(used.len(), 3);
+    }
+}
@@ -0,0 +1,471 @@
+//! Declarative description of the Genshin card: where every block goes and how
+//! its text is drawn. The built-in layout reproduces the original design.
+//!
+//! Artifact coordinates are relative to the artifact column, which starts at
+//! `artifacts.x` and moves right by `artifacts.step` per slot.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    path::{Component, Path},
+};
+
+use gen_utils::fonts;
+use image::Rgba;
+use once_cell::sync::Lazy;
+use rusttype::Font;
+use serde::Deserialize;
+
+use crate::constants::FONT;
+
+pub const DEFAULT_LAYOUT: &str = include_str!("../../../assets/layouts/default.json");
+/// Format version of layout files this build understands.
+pub const LAYOUT_VERSION: u32 = 1;
+
+pub static BUILTIN: Lazy<Layout> =
+    Lazy::new(|| Layout::parse(DEFAULT_LAYOUT).expect("built-in layout is invalid"));
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    /// Centered on `x` and `y` both.
+    Center,
+}
+
+fn white() -> [u8; 4] {
+    [255, 255, 255, 255]
+}
+
+/// A line of text. `x` is the left edge, right edge or center depending on `align`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Text {
+    pub x: i32,
+    pub y: i32,
+    pub size: f32,
+    #[serde(default)]
+    pub align: Align,
+    #[serde(default = "white")]
+    pub color: [u8; 4],
+    /// Text wider than this is scaled down to fit.
+    pub max_width: Option<u32>,
+    /// A bundled font (`genshin`, `hsr`) or a `.ttf`/`.otf` file next to the
+    /// layout. Defaults to the Genshin font.
+    pub font: Option<String>,
+}
+
+impl Text {
+    pub fn rgba(&self) -> Rgba<u8> {
+        Rgba(self.color)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Layout {
+    pub version: u32,
+    pub splash: Splash,
+    pub name: Text,
+    pub level: Text,
+    pub skills: Skills,
+    pub constellations: Constellations,
+    pub weapon: Weapon,
+    pub stats: StatList,
+    pub artifacts: Artifacts,
+    pub total: Total,
+    pub sets: Sets,
+    pub counter: Text,
+    /// Font files named by `Text::font`, loaded when parsing.
+    #[serde(skip)]
+    fonts: BTreeMap<String, &'static Font<'static>>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Splash {
+    pub default: Rect,
+    /// Per character id, for splash arts framed differently.
+    #[serde(default)]
+    pub overrides: BTreeMap<u32, Rect>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Skills {
+    pub icon: Rect,
+    pub level: Text,
+    /// Offset between skills on the y axis.
+    pub step: i32,
+    /// Level color when boosted past 9.
+    pub boosted_color: [u8; 4],
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Constellations {
+    pub frame: Rect,
+    /// Constellation icon inside the frame, relative to it.
+    pub icon: Rect,
+    pub step: i32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Weapon {
+    pub icon: Rect,
+    pub rarity: Point,
+    pub refinement: Text,
+    pub name: Text,
+    pub level: Text,
+    pub base_attack: Text,
+    pub base_attack_icon: Point,
+    pub sub_stat: Text,
+    pub sub_stat_icon: Point,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StatList {
+    pub value: Text,
+    pub label: Text,
+    pub step: i32,
+    /// Element icon, on the y of the last row.
+    pub element_icon_x: i32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Artifacts {
+    pub x: i32,
+    pub step: i32,
+    pub icon: Rect,
+    pub grade: Point,
+    pub score: Text,
+    pub main_type: Text,
+    /// Main stat icon, placed `main_icon_gap` left of the main stat name.
+    pub main_icon_gap: i32,
+    pub main_value: Text,
+    pub level: Text,
+    pub substats: Substats,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Substats {
+    pub y: i32,
+    pub step: i32,
+    /// Offset used instead of `step` for an empty substat slot.
+    pub empty_step: i32,
+    pub icon_x: i32,
+    pub name: Text,
+    /// Used for names wider than `long_name_width` at the regular size.
+    pub long_name: Text,
+    pub long_name_width: i32,
+    pub value: Text,
+    pub dim_color: [u8; 4],
+    /// Roll values (`a+b+c`) drawn above each substat.
+    pub rolls: Text,
+    pub markers: Markers,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Markers {
+    pub x: i32,
+    pub y: i32,
+    pub radius: i32,
+    pub spacing: i32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Total {
+    pub label: Text,
+    pub value: Text,
+    pub grade: Point,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Sets {
+    pub name: Text,
+    pub count: Text,
+    /// Rows start here when two sets are shown.
+    pub two_set_y: i32,
+    pub step: i32,
+    pub full_color: [u8; 4],
+}
+
+impl Layout {
+    /// Parses a layout that may only use bundled fonts.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::parse_in(s, None)
+    }
+    /// Parses a layout whose font files are looked up in `dir`.
+    pub fn parse_in(s: &str, dir: Option<&Path>) -> Result<Self, String> {
+        let mut layout: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if layout.version != LAYOUT_VERSION {
+            return Err(format!(
+                "unsupported layout version {} (expected {})",
+                layout.version, LAYOUT_VERSION
+            ));
+        }
+        layout.validate()?;
+        layout.load_fonts(dir)?;
+        Ok(layout)
+    }
+    /// Parses every `*.json` file in `dir`, keyed by file stem. A file that
+    /// fails to parse is returned as its error so the others stay usable.
+    pub fn load_dir(
+        dir: impl AsRef<Path>,
+    ) -> Result<BTreeMap<String, Result<Layout, String>>, String> {
+        let dir = dir.as_ref();
+        let mut layouts = BTreeMap::new();
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let layout = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| Self::parse_in(&data, Some(dir)))
+                .map_err(|e| format!("{}: {}", path.display(), e));
+            layouts.insert(name.to_string(), layout);
+        }
+        Ok(layouts)
+    }
+    pub fn splash(&self, cid: u32) -> Rect {
+        self.splash
+            .overrides
+            .get(&cid)
+            .copied()
+            .unwrap_or(self.splash.default)
+    }
+    /// Font of `text`, the Genshin font unless it names another one.
+    pub fn font(&self, text: &Text) -> &'static Font<'static> {
+        let Some(key) = &text.font else {
+            return &FONT;
+        };
+        match fonts::bundled(key) {
+            Some(font) => font,
+            None => self.fonts.get(key).copied().unwrap_or(&FONT),
+        }
+    }
+    fn texts(&self) -> Vec<(&'static str, &Text)> {
+        let (w, a, s) = (&self.weapon, &self.artifacts, &self.artifacts.substats);
+        vec![
+            ("name", &self.name),
+            ("level", &self.level),
+            ("skills.level", &self.skills.level),
+            ("weapon.refinement", &w.refinement),
+            ("weapon.name", &w.name),
+            ("weapon.level", &w.level),
+            ("weapon.base_attack", &w.base_attack),
+            ("weapon.sub_stat", &w.sub_stat),
+            ("stats.value", &self.stats.value),
+            ("stats.label", &self.stats.label),
+            ("artifacts.score", &a.score),
+            ("artifacts.main_type", &a.main_type),
+            ("artifacts.main_value", &a.main_value),
+            ("artifacts.level", &a.level),
+            ("artifacts.substats.name", &s.name),
+            ("artifacts.substats.long_name", &s.long_name),
+            ("artifacts.substats.value", &s.value),
+            ("artifacts.substats.rolls", &s.rolls),
+            ("total.label", &self.total.label),
+            ("total.value", &self.total.value),
+            ("sets.name", &self.sets.name),
+            ("sets.count", &self.sets.count),
+            ("counter", &self.counter),
+        ]
+    }
+    /// Rejects values that cannot be drawn, naming the offending field.
+    fn validate(&self) -> Result<(), String> {
+        for (field, text) in self.texts() {
+            if !(text.size.is_finite() && text.size > 0.0) {
+                return Err(format!("{}.size must be greater than 0", field));
+            }
+            if text.max_width == Some(0) {
+                return Err(format!("{}.max_width must be greater than 0", field));
+            }
+        }
+        let rects = [
+            ("splash.default", &self.splash.default),
+            ("skills.icon", &self.skills.icon),
+            ("constellations.frame", &self.constellations.frame),
+            ("constellations.icon", &self.constellations.icon),
+            ("weapon.icon", &self.weapon.icon),
+            ("artifacts.icon", &self.artifacts.icon),
+        ];
+        let overrides = self
+            .splash
+            .overrides
+            .values()
+            .map(|r| ("splash.overrides", r));
+        for (field, rect) in rects.into_iter().chain(overrides) {
+            if rect.width == 0 || rect.height == 0 {
+                return Err(format!("{} must not be empty", field));
+            }
+        }
+        let steps = [
+            ("skills.step", self.skills.step),
+            ("constellations.step", self.constellations.step),
+            ("stats.step", self.stats.step),
+            ("artifacts.step", self.artifacts.step),
+            ("artifacts.substats.step", self.artifacts.substats.step),
+            (
+                "artifacts.substats.empty_step",
+                self.artifacts.substats.empty_step,
+            ),
+            ("sets.step", self.sets.step),
+        ];
+        for (field, step) in steps {
+            if step == 0 {
+                return Err(format!("{} must not be 0", field));
+            }
+        }
+        let markers = &self.artifacts.substats.markers;
+        if markers.radius <= 0 || markers.spacing <= 0 {
+            return Err(
+                "artifacts.substats.markers radius and spacing must be greater than 0".to_string(),
+            );
+        }
+        if self.artifacts.substats.long_name_width <= 0 {
+            return Err("artifacts.substats.long_name_width must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+    /// Checks the fonts named by the texts and loads the font files. Those must
+    /// sit in `dir` or below it, and stay loaded for the rest of the process
+    /// like the layouts.
+    fn load_fonts(&mut self, dir: Option<&Path>) -> Result<(), String> {
+        let keys = self
+            .texts()
+            .into_iter()
+            .filter_map(|(_, t)| t.font.clone())
+            .collect::<BTreeSet<_>>();
+        for key in keys {
+            if fonts::bundled(&key).is_some() {
+                continue;
+            }
+            let file = Path::new(&key);
+            let is_font = matches!(
+                file.extension().and_then(OsStr::to_str),
+                Some("ttf" | "otf")
+            );
+            if !is_font {
+                return Err(format!(
+                    "font {} is neither one of {:?} nor a .ttf/.otf file",
+                    key,
+                    fonts::BUNDLED
+                ));
+            }
+            if !file.components().all(|c| matches!(c, Component::Normal(_))) {
+                return Err(format!(
+                    "font {} must be a path inside the layout directory",
+                    key
+                ));
+            }
+            let dir = dir.ok_or_else(|| format!("font {} needs a layout directory", key))?;
+            let path = dir.join(file);
+            let data = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let font = Font::try_from_vec(data)
+                .ok_or_else(|| format!("{} is not a valid font", path.display()))?;
+            self.fonts.insert(key, Box::leak(Box::new(font)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The built-in layout with `edit` applied to its JSON.
+    fn layout_with(edit: impl FnOnce(&mut serde_json::Value)) -> String {
+        let mut value: serde_json::Value = serde_json::from_str(DEFAULT_LAYOUT).unwrap();
+        edit(&mut value);
+        value.to_string()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("gen-layout-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn builtin_layout_parses() {
+        assert_eq!(BUILTIN.version, LAYOUT_VERSION);
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let s = layout_with(|v| v["version"] = (LAYOUT_VERSION + 1).into());
+        assert!(Layout::parse(&s).unwrap_err().contains("version"));
+    }
+
+    #[test]
+    fn rejects_values_that_cannot_be_drawn() {
+        type Edit = fn(&mut serde_json::Value);
+        let cases: [(Edit, &str); 5] = [
+            (|v| v["name"]["size"] = 0.into(), "name.size"),
+            (|v| v["level"]["max_width"] = 0.into(), "level.max_width"),
+            (|v| v["artifacts"]["step"] = 0.into(), "artifacts.step"),
+            (|v| v["weapon"]["icon"]["width"] = 0.into(), "weapon.icon"),
+            (
+                |v| v["artifacts"]["substats"]["markers"]["radius"] = 0.into(),
+                "markers",
+            ),
+        ];
+        for (edit, field) in cases {
+            let err = Layout::parse(&layout_with(edit)).unwrap_err();
+            assert!(err.contains(field), "{} not in {}", field, err);
+        }
+    }
+
+    #[test]
+    fn accepts_bundled_fonts() {
+        let s = layout_with(|v| v["name"]["font"] = "hsr".into());
+        let layout = Layout::parse(&s).unwrap();
+        assert_eq!(layout.name.font.as_deref(), Some("hsr"));
+        assert!(layout.fonts.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_and_escaping_fonts() {
+        for font in ["comic", "../font.ttf", "/etc/font.ttf"] {
+            let s = layout_with(|v| v["name"]["font"] = font.into());
+            assert!(Layout::parse(&s).is_err(), "{} accepted", font);
+        }
+        // Font files are only looked up next to a layout file.
+        let s = layout_with(|v| v["name"]["font"] = "font.ttf".into());
+        assert!(Layout::parse(&s).unwrap_err().contains("directory"));
+        let dir = temp_dir("missing-font");
+        assert!(Layout::parse_in(&s, Some(&dir)).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_dir_keeps_good_layouts() {
+        let dir = temp_dir("load-dir");
+        std::fs::write(dir.join("good.json"), DEFAULT_LAYOUT).unwrap();
+        let bad = layout_with(|v| v["stats"]["step"] = 0.into());
+        std::fs::write(dir.join("bad.json"), bad).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a layout").unwrap();
+        let layouts = Layout::load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(layouts.len(), 2);
+        assert!(layouts["good"].is_ok());
+        assert!(layouts["bad"].as_ref().unwrap_err().contains("stats.step"));
+    }
+}
@@ -1,6 +1,9 @@
+mod compact;
 pub mod constants;
 pub mod default;
 pub mod dupe;
 pub mod gen;
+pub mod layout;
 pub mod locale;
+pub mod style;
 pub use enka_api;
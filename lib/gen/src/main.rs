@@ -19,6 +19,7 @@ async fn main() {
         &icons,
         counter,
         format.clone(),
+        gen::gen::CardOptions::default(),
     )
     .await
     .unwrap();
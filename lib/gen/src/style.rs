@@ -0,0 +1,133 @@
+//! Selectable card designs. Every style draws the same character data; the
+//! caller picks one with [`CardStyle`].
+
+use std::{fmt::Display, str::FromStr};
+
+use async_trait::async_trait;
+use enka_api::{api::Api, character::Character, icon::IconData};
+use gen_utils::style::{parse_style, CardRenderer, Styles};
+use image::DynamicImage;
+
+use crate::{
+    compact,
+    gen::{render_classic, CardOptions, Lang, ScoreCounter},
+};
+
+/// Everything a card design needs to draw one character.
+pub struct CardInput<'a> {
+    pub data: &'a Character,
+    pub api: &'a Api,
+    pub lang: &'a Lang,
+    pub icons: &'a IconData,
+    pub counter: ScoreCounter,
+    pub options: CardOptions,
+}
+
+/// The original 1920x1080 landscape card.
+pub struct Classic;
+
+#[async_trait]
+impl<'a> CardRenderer<CardInput<'a>> for Classic {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+    async fn render(&self, input: CardInput<'a>) -> Option<DynamicImage> {
+        render_classic(input).await
+    }
+}
+
+/// A 720x1280 portrait card for phone screens.
+pub struct Compact;
+
+#[async_trait]
+impl<'a> CardRenderer<CardInput<'a>> for Compact {
+    fn name(&self) -> &'static str {
+        "compact"
+    }
+    async fn render(&self, input: CardInput<'a>) -> Option<DynamicImage> {
+        compact::render(input).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardStyle {
+    #[default]
+    Classic,
+    Compact,
+}
+
+impl CardStyle {
+    pub fn renderer(&self) -> &'static dyn for<'a> CardRenderer<CardInput<'a>> {
+        match self {
+            CardStyle::Classic => &Classic,
+            CardStyle::Compact => &Compact,
+        }
+    }
+    /// Rejects options the style cannot draw.
+    pub fn check(&self, options: &CardOptions) -> Result<(), String> {
+        if *self != CardStyle::Compact {
+            return Ok(());
+        }
+        if options.layout.is_some() {
+            return Err("the compact style does not support layouts".to_string());
+        }
+        if options.roll_markers {
+            return Err("the compact style does not draw roll markers".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Styles for CardStyle {
+    const ALL: &'static [Self] = &[CardStyle::Classic, CardStyle::Compact];
+    fn name(&self) -> &'static str {
+        self.renderer().name()
+    }
+}
+
+impl Display for CardStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CardStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_style(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::BUILTIN;
+
+    #[test]
+    fn parses_style_names() {
+        assert_eq!("Compact".parse(), Ok(CardStyle::Compact));
+        assert_eq!("default".parse(), Ok(CardStyle::Classic));
+        assert!("wide".parse::<CardStyle>().is_err());
+        for style in CardStyle::ALL {
+            assert_eq!(style.to_string().parse(), Ok(*style));
+        }
+    }
+
+    #[test]
+    fn compact_rejects_classic_only_options() {
+        let layout = CardOptions {
+            layout: Some(&BUILTIN),
+            ..Default::default()
+        };
+        let rolls = CardOptions {
+            roll_markers: true,
+            ..Default::default()
+        };
+        for options in [layout, rolls] {
+            assert!(CardStyle::Classic.check(&options).is_ok());
+            assert!(CardStyle::Compact.check(&options).is_err());
+        }
+        assert!(CardStyle::Compact.check(&CardOptions::default()).is_ok());
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use gen_utils::profiles::PROFILES;
+
+use crate::gen::ScoreCounter;
+
+/// The character's default counter from the score profiles file, `Attack` when it has none.
+pub fn get_default(cid: &u32) -> ScoreCounter {
+    PROFILES
+        .hsr
+        .get(cid)
+        .and_then(|p| p.counter.parse().ok())
+        .unwrap_or(ScoreCounter::Attack)
+}
+
+/// Characters of `names`, the StarRailRes index, without a score profile.
+pub fn missing_defaults(names: &BTreeMap<u32, String>) -> BTreeMap<u32, String> {
+    names
+        .iter()
+        .filter(|(id, _)| !PROFILES.hsr.contains_key(id))
+        .map(|(id, name)| (*id, name.clone()))
+        .collect()
+}
+
+/// Score profiles whose counter cannot be parsed, with the parse error.
+pub fn invalid_defaults() -> BTreeMap<u32, String> {
+    PROFILES
+        .hsr
+        .iter()
+        .filter_map(|(id, p)| Some((*id, p.counter.parse::<ScoreCounter>().err()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_characters_without_a_profile() {
+        let names = BTreeMap::from([
+            (1001, "March 7th".to_string()),
+            (9999, "Newcomer".to_string()),
+        ]);
+        let missing = missing_defaults(&names);
+        assert_eq!(missing, BTreeMap::from([(9999, "Newcomer".to_string())]));
+    }
+}
@@ -1,33 +1,79 @@
-use std::{io::Cursor, str::FromStr};
+use std::{fmt::Display, io::Cursor, str::FromStr};
 
-use gen_utils::{get_hsr_grade_image, get_rarity_image};
+use gen_utils::{
+    fonts::HSR as FONT,
+    get_hsr_grade_image, get_rarity_image,
+    grades::{grade_set_for, GradeSet, GRADES},
+    weights::{format_weights, parse_weights},
+};
 use image::{
     imageops::{crop_imm, overlay, resize, FilterType},
-    DynamicImage, ImageOutputFormat,
+    DynamicImage, ImageOutputFormat, Rgba,
 };
-use imageproc::drawing::draw_text_mut;
+use imageproc::drawing::{draw_filled_circle_mut, draw_text_mut, text_size};
 use localization::t;
 use mihomo_api::{
     api::Api,
-    character::{Attribute, Character, Relic},
+    character::{Attribute, Character, Relic, SkillTree},
 };
 use rusttype::{Font, Scale};
 use std::io::BufWriter;
 
-use crate::format::ImageFormat;
-use once_cell::sync::Lazy;
+use crate::{
+    format::ImageFormat,
+    rolls::SubstatRolls,
+    style::{CardInput, CardStyle},
+};
+
+/// Optional settings of the card.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CardOptions {
+    /// Marks every substat roll with a dot colored by its tier.
+    pub roll_markers: bool,
+    /// Grade thresholds, `None` for the counter's own set.
+    pub grades: Option<&'static GradeSet>,
+    pub style: CardStyle,
+}
 
-static FONT: Lazy<Font> =
-    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../../assets/hsr-font.ttf")).unwrap());
+/// Marker colors for roll tiers 1 (low) to 3 (high).
+const ROLL_TIER_COLORS: [Rgba<u8>; 3] = [
+    Rgba([170, 170, 170, 255]),
+    Rgba([100, 180, 255, 255]),
+    Rgba([255, 200, 60, 255]),
+];
 
 pub async fn generate(
     api: &Api,
     character: &Character,
-    mut base_image: DynamicImage,
+    base_image: DynamicImage,
     format: ImageFormat,
     lang: &str,
     counter: &ScoreCounter,
+    options: CardOptions,
 ) -> Option<Vec<u8>> {
+    let input = CardInput {
+        api,
+        character,
+        base_image,
+        lang,
+        counter,
+        options,
+    };
+    let image = options.style.renderer().render(input).await?;
+    convert(image, format).await
+}
+
+/// The original landscape card.
+pub(crate) async fn render_classic(input: CardInput<'_>) -> Option<DynamicImage> {
+    let CardInput {
+        api,
+        character,
+        mut base_image,
+        lang,
+        counter,
+        options,
+    } = input;
+    let grades = options.grades.unwrap_or_else(|| counter.grades());
     let lang = match lang {
         "en" | "en-US" | "en-GB" => "en-US",
         _ => "ja-JP",
@@ -178,6 +224,20 @@ pub async fn generate(
             &level,
         );
         for (rindex, affix) in relic.sub_affix.iter().enumerate() {
+            if options.roll_markers {
+                let tiers = SubstatRolls::of(affix).tiers();
+                for (i, tier) in tiers.iter().enumerate() {
+                    draw_filled_circle_mut(
+                        &mut base_image,
+                        (
+                            1552 + 7 * i as i32,
+                            106 + 25 * rindex as i32 + 173 * index as i32,
+                        ),
+                        2,
+                        ROLL_TIER_COLORS[*tier as usize - 1],
+                    );
+                }
+            }
             draw_text_resized(
                 &mut base_image,
                 image::Rgba([255, 255, 255, 255]),
@@ -202,7 +262,7 @@ pub async fn generate(
         let relic_score = get_score(relic, counter);
         total_score += relic_score;
         let score = format!("{:.1}", relic_score);
-        let img = get_score_image(relic_score, Some(index))?;
+        let img = get_score_image(relic_score, Some(index), grades)?;
         let img = resize(&img, 80, 80, FilterType::Triangle);
         overlay(&mut base_image, &img, 1780, 75 + 173 * index as i64);
         draw_text_mut(
@@ -263,9 +323,15 @@ pub async fn generate(
             100 + 80 * plus_index,
         );
         let level = format!("Lv.{}", skill.level);
+        let boosted = trace_level(character, &skill.id).is_some_and(|lv| skill.level > lv);
+        let color = if boosted {
+            image::Rgba([0, 255, 255, 255])
+        } else {
+            image::Rgba([255, 255, 255, 255])
+        };
         draw_text_mut(
             &mut base_image,
-            image::Rgba([255, 255, 255, 255]),
+            color,
             780 + plus as i32 * 60,
             160 + 80 * plus_index as i32,
             Scale::uniform(15.0),
@@ -273,6 +339,52 @@ pub async fn generate(
             &level,
         );
     }
+    // relic sets
+    let mut y = 550;
+    for set in character.relic_sets.iter() {
+        // The icon is decoration; the set still shows without it.
+        if let Ok(img) = api.asset(&set.icon).await {
+            let img = resize(&img, 30, 30, FilterType::Triangle);
+            overlay(&mut base_image, &img, 850, y as i64);
+        }
+        let count = set.num;
+        let pieces = t!(lang, "main:hsr.setPieces", count);
+        draw_text_resized(
+            &mut base_image,
+            image::Rgba([255, 255, 255, 255]),
+            890,
+            y + 3,
+            Scale::uniform(22.0),
+            &font,
+            &format!("{} {}", pieces, set.name),
+            340,
+        );
+        let width = |s: &str| text_size(Scale::uniform(15.0), &font, s).0;
+        for (line, text) in wrap_text(&set.desc, width, 380, 2).iter().enumerate() {
+            draw_text_mut(
+                &mut base_image,
+                image::Rgba([210, 210, 210, 255]),
+                850,
+                y + 32 + 17 * line as i32,
+                Scale::uniform(15.0),
+                &font,
+                text,
+            );
+        }
+        y += 68;
+    }
+    // bonus abilities
+    for (index, trace) in major_traces(character).iter().enumerate() {
+        if let Ok(img) = api.asset(&trace.icon).await {
+            let mut img = resize(&img, 50, 50, FilterType::Triangle);
+            if trace.level == 0 {
+                for p in img.pixels_mut() {
+                    p.0[3] /= 3;
+                }
+            }
+            overlay(&mut base_image, &img, 705, 280 + 70 * index as i64);
+        }
+    }
     // all
     let total = t!(lang, "main:hsr.total");
     draw_text_mut(
@@ -304,10 +416,10 @@ pub async fn generate(
         &font,
         &score,
     );
-    let img = get_score_image(total_score, None)?;
+    let img = get_score_image(total_score, None, grades)?;
     let img = resize(&img, 150, 150, FilterType::Triangle);
     overlay(&mut base_image, &img, 760, 800);
-    convert(base_image, format).await
+    Some(base_image)
 }
 
 pub async fn convert(image: DynamicImage, format: ImageFormat) -> Option<Vec<u8>> {
@@ -326,6 +438,28 @@ pub async fn convert(image: DynamicImage, format: ImageFormat) -> Option<Vec<u8>
     }
 }
 
+/// Level of a skill from its trace, before Eidolon bonuses. Trace ids are the
+/// skill ids with the character id followed by a 3-digit index, e.g. `1102001`
+/// for skill `110201`.
+fn trace_level(character: &Character, skill_id: &str) -> Option<u8> {
+    let index = skill_id.strip_prefix(character.id.as_str())?;
+    let trace_id = format!("{}0{}", character.id, index);
+    character
+        .skill_trees
+        .iter()
+        .find(|t| t.id == trace_id)
+        .map(|t| t.level)
+}
+
+/// The three bonus abilities (major traces), locked ones included.
+fn major_traces(character: &Character) -> Vec<&SkillTree> {
+    character
+        .skill_trees
+        .iter()
+        .filter(|t| matches!(t.anchor.as_str(), "Point06" | "Point07" | "Point08"))
+        .collect()
+}
+
 fn resolve_stats(character: &Character) -> Option<Vec<Attribute>> {
     let mut attr = character.attributes.iter().clone();
     let additional = character.additions.iter().clone();
@@ -370,6 +504,9 @@ fn resolve_stats(character: &Character) -> Option<Vec<Attribute>> {
 }
 
 fn get_score(relic: &Relic, counter: &ScoreCounter) -> f64 {
+    if let Some(weights) = counter.weights() {
+        return weights.score(relic);
+    }
     let mut score = 0.0;
     for affix in relic.sub_affix.iter() {
         match affix.field.as_str() {
@@ -462,6 +599,7 @@ fn get_score(relic: &Relic, counter: &ScoreCounter) -> f64 {
 
 #[derive(PartialEq)]
 pub enum ScoreCounter {
+    Custom(ScoreWeights),
     Attack,
     Hp,
     Defense,
@@ -472,9 +610,196 @@ pub enum ScoreCounter {
     SpeedOnly,
     HpOnly,
     EhrOnly,
+    /// Effect RES and SPD for sustains that must not be controlled.
+    Res,
+    /// Substats of Outgoing Healing builds: HP, SPD and Effect RES.
+    Healing,
+    /// Substats of Energy Regeneration Rate supports: SPD, Break Effect and survivability.
+    EnergyRegen,
+    CritOnly,
+}
+
+/// Per-stat weights of a custom score.
+///
+/// Every substat is counted in rolls, i.e. divided by the highest single roll of
+/// a 5-star relic, so a weight of 1 values one max roll of any stat the same.
+/// Written as `key=weight` pairs separated by commas, e.g. `crit=1,spd=1,atk%=0.5`.
+/// `crit=w` is shorthand for `cr=w,cd=w`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScoreWeights {
+    pub hp: f64,
+    pub atk: f64,
+    pub def: f64,
+    pub hp_percent: f64,
+    pub atk_percent: f64,
+    pub def_percent: f64,
+    pub crit_rate: f64,
+    pub crit_dmg: f64,
+    pub speed: f64,
+    pub effect_hit: f64,
+    pub effect_res: f64,
+    pub break_effect: f64,
+}
+
+impl ScoreWeights {
+    const NONE: ScoreWeights = ScoreWeights {
+        hp: 0.0,
+        atk: 0.0,
+        def: 0.0,
+        hp_percent: 0.0,
+        atk_percent: 0.0,
+        def_percent: 0.0,
+        crit_rate: 0.0,
+        crit_dmg: 0.0,
+        speed: 0.0,
+        effect_hit: 0.0,
+        effect_res: 0.0,
+        break_effect: 0.0,
+    };
+    /// Score of one max roll at weight 1. Matches a max CRIT roll of the presets,
+    /// so custom scores share their grade scale.
+    pub const ROLL_SCORE: f64 = 6.48;
+    const KEYS: [&'static str; 12] = [
+        "hp", "atk", "def", "hp%", "atk%", "def%", "cr", "cd", "spd", "ehr", "res", "be",
+    ];
+
+    fn to_array(self) -> [f64; 12] {
+        [
+            self.hp,
+            self.atk,
+            self.def,
+            self.hp_percent,
+            self.atk_percent,
+            self.def_percent,
+            self.crit_rate,
+            self.crit_dmg,
+            self.speed,
+            self.effect_hit,
+            self.effect_res,
+            self.break_effect,
+        ]
+    }
+    /// Weight of a substat, by its Mihomo `field` and `percent` flag.
+    pub fn get(&self, field: &str, percent: bool) -> f64 {
+        match (field, percent) {
+            ("hp", false) => self.hp,
+            ("atk", false) => self.atk,
+            ("def", false) => self.def,
+            ("hp", true) => self.hp_percent,
+            ("atk", true) => self.atk_percent,
+            ("def", true) => self.def_percent,
+            ("crit_rate", _) => self.crit_rate,
+            ("crit_dmg", _) => self.crit_dmg,
+            ("spd", _) => self.speed,
+            ("effect_hit", _) => self.effect_hit,
+            ("effect_res", _) => self.effect_res,
+            ("break_dmg", _) => self.break_effect,
+            _ => 0.0,
+        }
+    }
+    pub fn score(&self, relic: &Relic) -> f64 {
+        relic
+            .sub_affix
+            .iter()
+            .filter_map(|affix| {
+                let max = max_roll(&affix.field, affix.percent)?;
+                Some(affix.value / max * self.get(&affix.field, affix.percent))
+            })
+            .sum::<f64>()
+            * Self::ROLL_SCORE
+    }
+}
+
+/// Highest single roll of a substat on a 5-star relic, percentages as fractions.
+pub fn max_roll(field: &str, percent: bool) -> Option<f64> {
+    Some(match (field, percent) {
+        ("hp", false) => 42.337549,
+        ("atk", false) | ("def", false) => 21.168773,
+        ("hp", true) | ("atk", true) => 0.0432,
+        ("def", true) => 0.054,
+        ("crit_rate", _) => 0.0324,
+        ("crit_dmg", _) => 0.0648,
+        ("spd", _) => 2.6,
+        ("effect_hit", _) | ("effect_res", _) => 0.0432,
+        ("break_dmg", _) => 0.0648,
+        _ => return None,
+    })
+}
+
+impl Display for ScoreWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_weights(&Self::KEYS, &self.to_array()))
+    }
+}
+
+impl FromStr for ScoreWeights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [hp, atk, def, hp_percent, atk_percent, def_percent, crit_rate, crit_dmg, speed, effect_hit, effect_res, break_effect] =
+            parse_weights(s, &Self::KEYS)?;
+        Ok(Self {
+            hp,
+            atk,
+            def,
+            hp_percent,
+            atk_percent,
+            def_percent,
+            crit_rate,
+            crit_dmg,
+            speed,
+            effect_hit,
+            effect_res,
+            break_effect,
+        })
+    }
 }
 
 impl ScoreCounter {
+    /// Weights of the counters scored per roll. Outgoing Healing and Energy
+    /// Regeneration Rate only exist as main stats, so their presets weight the
+    /// substats those builds go for.
+    pub fn weights(&self) -> Option<ScoreWeights> {
+        let none = ScoreWeights::NONE;
+        Some(match self {
+            ScoreCounter::Custom(weights) => *weights,
+            ScoreCounter::Res => ScoreWeights {
+                effect_res: 1.0,
+                speed: 1.0,
+                hp_percent: 0.5,
+                def_percent: 0.5,
+                ..none
+            },
+            ScoreCounter::Healing => ScoreWeights {
+                hp_percent: 1.0,
+                speed: 1.0,
+                effect_res: 0.5,
+                ..none
+            },
+            ScoreCounter::EnergyRegen => ScoreWeights {
+                speed: 1.0,
+                break_effect: 0.5,
+                hp_percent: 0.5,
+                def_percent: 0.5,
+                effect_res: 0.5,
+                ..none
+            },
+            ScoreCounter::CritOnly => ScoreWeights {
+                crit_rate: 1.0,
+                crit_dmg: 1.0,
+                ..none
+            },
+            _ => return None,
+        })
+    }
+    /// Grade thresholds used for this counter unless the caller picks a set.
+    pub fn grades(&self) -> &'static GradeSet {
+        let key = match self {
+            ScoreCounter::Custom(_) => "custom".to_string(),
+            _ => self.to_string(),
+        };
+        grade_set_for(&GRADES.hsr, &key)
+    }
     pub fn to_string_locale(&self, lang: &str) -> String {
         match lang {
             "ja-JP" | "ja" => self.ja(),
@@ -484,6 +809,7 @@ impl ScoreCounter {
     }
     pub fn ja(&self) -> &str {
         match self {
+            ScoreCounter::Custom(_) => "カスタム",
             ScoreCounter::Attack => "攻撃型",
             ScoreCounter::Hp => "HP型",
             ScoreCounter::Defense => "防御型",
@@ -494,10 +820,15 @@ impl ScoreCounter {
             ScoreCounter::HpOnly => "HPのみ",
             ScoreCounter::EhrOnly => "効果命中のみ",
             ScoreCounter::SpeedOnly => "速度のみ",
+            ScoreCounter::Res => "効果抵抗型",
+            ScoreCounter::Healing => "治癒型",
+            ScoreCounter::EnergyRegen => "EP回復型",
+            ScoreCounter::CritOnly => "会心のみ",
         }
     }
     pub fn en(&self) -> &str {
         match self {
+            ScoreCounter::Custom(_) => "Custom",
             ScoreCounter::BeOnly => "BeOnly",
             ScoreCounter::Attack => "Attack",
             ScoreCounter::Hp => "Hp",
@@ -508,15 +839,19 @@ impl ScoreCounter {
             ScoreCounter::HpOnly => "HpOnly",
             ScoreCounter::EhrOnly => "EhrOnly",
             ScoreCounter::SpeedOnly => "SpeedOnly",
+            ScoreCounter::Res => "Res",
+            ScoreCounter::Healing => "Healing",
+            ScoreCounter::EnergyRegen => "EnergyRegen",
+            ScoreCounter::CritOnly => "CritOnly",
         }
     }
 }
 
 impl FromStr for ScoreCounter {
-    type Err = ();
+    type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "attack" => Ok(ScoreCounter::Attack),
+            "attack" | "normal" => Ok(ScoreCounter::Attack),
             "hp" => Ok(ScoreCounter::Hp),
             "defense" => Ok(ScoreCounter::Defense),
             "ehr" => Ok(ScoreCounter::Ehr),
@@ -526,7 +861,12 @@ impl FromStr for ScoreCounter {
             "hp_only" => Ok(ScoreCounter::HpOnly),
             "ehr_only" => Ok(ScoreCounter::EhrOnly),
             "speed_only" => Ok(ScoreCounter::SpeedOnly),
-            _ => Err(()),
+            "res" => Ok(ScoreCounter::Res),
+            "healing" => Ok(ScoreCounter::Healing),
+            "energy_regen" => Ok(ScoreCounter::EnergyRegen),
+            "crit_only" => Ok(ScoreCounter::CritOnly),
+            s if s.contains('=') => Ok(ScoreCounter::Custom(s.parse()?)),
+            _ => Err(format!("{} is not ScoreCounter", s)),
         }
     }
 }
@@ -534,6 +874,7 @@ impl FromStr for ScoreCounter {
 impl ToString for ScoreCounter {
     fn to_string(&self) -> String {
         match self {
+            ScoreCounter::Custom(weights) => weights.to_string(),
             ScoreCounter::BeOnly => "be_only".to_string(),
             ScoreCounter::Attack => "attack".to_string(),
             ScoreCounter::Hp => "hp".to_string(),
@@ -544,6 +885,10 @@ impl ToString for ScoreCounter {
             ScoreCounter::HpOnly => "hp_only".to_string(),
             ScoreCounter::EhrOnly => "ehr_only".to_string(),
             ScoreCounter::SpeedOnly => "speed_only".to_string(),
+            ScoreCounter::Res => "res".to_string(),
+            ScoreCounter::Healing => "healing".to_string(),
+            ScoreCounter::EnergyRegen => "energy_regen".to_string(),
+            ScoreCounter::CritOnly => "crit_only".to_string(),
         }
     }
 }
@@ -570,47 +915,54 @@ fn draw_text_resized(
     draw_text_mut(canvas, color, x, y, scale, font, text)
 }
 
-struct Score {
-    a: f64,
-    s: f64,
-    ss: f64,
+/// Splits `text` into at most `max_lines` lines no wider than `max_width` as
+/// measured by `width`, breaking at spaces when there are any. The last line
+/// ends with `…` if cut.
+fn wrap_text(
+    text: &str,
+    width: impl Fn(&str) -> i32,
+    max_width: i32,
+    max_lines: usize,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for c in text.chars().filter(|c| *c != '\n') {
+        line.push(c);
+        if width(&line) <= max_width {
+            continue;
+        }
+        let cut = match line.rfind(' ') {
+            Some(i) if i > 0 => i,
+            _ => line.len() - c.len_utf8(),
+        };
+        let rest = line.split_off(cut);
+        lines.push(line);
+        line = rest.trim_start().to_string();
+        if lines.len() == max_lines {
+            let last = lines.last_mut().unwrap();
+            while width(&format!("{}…", last)) > max_width && last.pop().is_some() {}
+            last.push('…');
+            return lines;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
 }
 
-const TOTAL_SCORE: Score = Score {
-    a: 150.0,
-    s: 180.0,
-    ss: 210.0,
-};
-
-const SCORE_0: Score = Score {
-    a: 30.0,
-    s: 35.0,
-    ss: 40.0,
-};
-
-const SCORE_1: Score = Score {
-    a: 20.0,
-    s: 25.0,
-    ss: 30.0,
-};
-
-const SCORE_2: Score = Score {
-    a: 25.0,
-    s: 30.0,
-    ss: 35.0,
-};
-
-fn get_score_image(score: f64, place: Option<usize>) -> Option<DynamicImage> {
-    let score_selector = if let Some(place) = place {
-        match place {
-            0 | 1 => SCORE_0,
-            2 | 4 => SCORE_1,
-            3 | 5 => SCORE_2,
-            _ => SCORE_0,
-        }
-    } else {
-        TOTAL_SCORE
+fn get_score_image(score: f64, place: Option<usize>, grades: &GradeSet) -> Option<DynamicImage> {
+    let key = match place {
+        None => "total",
+        Some(0) => "head",
+        Some(1) => "hands",
+        Some(2) => "body",
+        Some(3) => "feet",
+        Some(4) => "sphere",
+        Some(5) => "rope",
+        Some(_) => "head",
     };
+    let score_selector = grades.get(key)?;
     let score = if score > score_selector.ss {
         "SS"
     } else if score > score_selector.s {
@@ -622,3 +974,224 @@ fn get_score_image(score: f64, place: Option<usize>) -> Option<DynamicImage> {
     };
     get_hsr_grade_image(score)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mihomo_api::character::{Element, Path, Properties, SubAffix};
+
+    fn affix(field: &str, percent: bool, value: f64) -> SubAffix {
+        SubAffix {
+            r#type: String::new(),
+            field: field.to_string(),
+            name: String::new(),
+            icon: String::new(),
+            value,
+            display: String::new(),
+            percent,
+            count: 1,
+            step: 2,
+        }
+    }
+
+    fn relic(sub_affix: Vec<SubAffix>) -> Relic {
+        Relic {
+            main_affix: Properties {
+                r#type: String::new(),
+                field: "hp".to_string(),
+                name: String::new(),
+                icon: String::new(),
+                value: 705.6,
+                display: String::new(),
+                percent: false,
+            },
+            sub_affix,
+            id: String::new(),
+            name: String::new(),
+            set_id: String::new(),
+            set_name: String::new(),
+            rarity: 5,
+            level: 15,
+            icon: String::new(),
+        }
+    }
+
+    #[test]
+    fn presets_use_their_grade_sets() {
+        let presets = [
+            ("attack", "default"),
+            ("hp", "default"),
+            ("defense", "defense"),
+            ("ehr", "ehr"),
+            ("be", "be"),
+            ("be_only", "be_only"),
+            ("speed", "speed"),
+            ("speed_only", "speed_only"),
+            ("hp_only", "hp_only"),
+            ("ehr_only", "ehr_only"),
+            ("res", "res"),
+            ("healing", "healing"),
+            ("energy_regen", "energy_regen"),
+            ("crit_only", "crit_only"),
+            ("crit=1", "custom"),
+        ];
+        for (name, set) in presets {
+            let counter = ScoreCounter::from_str(name).unwrap();
+            assert!(
+                std::ptr::eq(counter.grades(), &GRADES.hsr[set]),
+                "{} should use {}",
+                name,
+                set
+            );
+        }
+        for set in GRADES.hsr.keys() {
+            assert!(
+                set == "default" || presets.iter().any(|(_, s)| s == set),
+                "{} is not used by any counter",
+                set
+            );
+        }
+    }
+
+    #[test]
+    fn custom_weights_round_trip() {
+        let weights = ScoreWeights::from_str("crit=1,SPD=2,be=0.5").unwrap();
+        assert_eq!(weights.crit_rate, 1.0);
+        assert_eq!(weights.crit_dmg, 1.0);
+        assert_eq!(weights.speed, 2.0);
+        assert_eq!(weights.break_effect, 0.5);
+        assert_eq!(weights.to_string(), "cr=1,cd=1,spd=2,be=0.5");
+        assert_eq!(weights.to_string().parse(), Ok(weights));
+        assert!(matches!(
+            ScoreCounter::from_str("crit=1,spd=2,be=0.5"),
+            Ok(ScoreCounter::Custom(w)) if w == weights
+        ));
+    }
+
+    #[test]
+    fn custom_weights_reject_unknown_stats() {
+        assert!(ScoreWeights::from_str("em=1").is_err());
+        assert!(ScoreWeights::from_str("spd=0").is_err());
+        assert!(ScoreCounter::from_str("crit").is_err());
+    }
+
+    #[test]
+    fn custom_score_counts_rolls() {
+        let weights = ScoreWeights::from_str("crit=1,spd=0.5").unwrap();
+        let relic = relic(vec![
+            affix("crit_rate", true, 0.0324),
+            affix("crit_dmg", true, 0.0648),
+            affix("spd", false, 2.6),
+            affix("atk", true, 0.0432),
+        ]);
+        let score = weights.score(&relic);
+        assert!((score - ScoreWeights::ROLL_SCORE * 2.5).abs() < 1e-9);
+    }
+
+    /// One unit per ASCII character and two per wider one, like a monospace font.
+    fn mono(s: &str) -> i32 {
+        s.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+    }
+
+    #[test]
+    fn wraps_ascii_at_spaces() {
+        let lines = wrap_text("2-Pc: ATK increases by 12%.", mono, 12, 3);
+        assert_eq!(lines, ["2-Pc: ATK", "increases by", "12%."]);
+        assert!(lines.iter().all(|l| mono(l) <= 12));
+    }
+
+    #[test]
+    fn wraps_text_without_spaces_by_char() {
+        let lines = wrap_text("攻撃力が12%アップする。", mono, 8, 3);
+        assert_eq!(lines, ["攻撃力が", "12%アッ", "プする。"]);
+        assert!(lines.iter().all(|l| mono(l) <= 8));
+    }
+
+    #[test]
+    fn cuts_overflow_with_an_ellipsis() {
+        let lines = wrap_text("one two three four five six", mono, 9, 2);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "one two");
+        assert!(lines[1].ends_with('…'));
+        assert!(mono(&lines[1]) <= 9);
+        let lines = wrap_text("攻撃力が12%アップする。", mono, 8, 1);
+        assert_eq!(lines, ["攻撃力…"]);
+    }
+
+    fn trace(id: &str, anchor: &str, level: u8) -> SkillTree {
+        SkillTree {
+            id: id.to_string(),
+            level,
+            anchor: anchor.to_string(),
+            max_level: level.max(1),
+            icon: String::new(),
+            parent: None,
+        }
+    }
+
+    /// Seele with her skill traces and bonus abilities, the last one locked.
+    fn seele() -> Character {
+        let text = |s: &str| s.to_string();
+        Character {
+            id: text("1102"),
+            name: text("Seele"),
+            rarity: 5,
+            rank: 0,
+            level: 80,
+            icon: String::new(),
+            preview: String::new(),
+            portrait: String::new(),
+            path: Path {
+                id: text("Rogue"),
+                name: text("The Hunt"),
+                icon: String::new(),
+            },
+            element: Element {
+                id: text("Quantum"),
+                name: text("Quantum"),
+                icon: String::new(),
+                color: String::new(),
+            },
+            rank_icons: Vec::new(),
+            promotion: 6,
+            light_cone: None,
+            properties: Vec::new(),
+            attributes: Vec::new(),
+            additions: Vec::new(),
+            relic_sets: Vec::new(),
+            skills: Vec::new(),
+            skill_trees: vec![
+                trace("1102001", "Point01", 6),
+                trace("1102002", "Point02", 10),
+                trace("1102003", "Point03", 9),
+                trace("1102004", "Point04", 8),
+                trace("1102007", "Point05", 1),
+                trace("1102101", "Point06", 1),
+                trace("1102102", "Point07", 1),
+                trace("1102103", "Point08", 0),
+                trace("1102201", "Point09", 1),
+            ],
+            relics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn trace_level_maps_skill_ids_to_traces() {
+        let seele = seele();
+        assert_eq!(trace_level(&seele, "110201"), Some(6));
+        assert_eq!(trace_level(&seele, "110202"), Some(10));
+        assert_eq!(trace_level(&seele, "110204"), Some(8));
+        // another character's skill, and a skill without a trace
+        assert_eq!(trace_level(&seele, "110302"), None);
+        assert_eq!(trace_level(&seele, "110209"), None);
+    }
+
+    #[test]
+    fn major_traces_are_the_bonus_abilities() {
+        let seele = seele();
+        let traces = major_traces(&seele);
+        let ids = traces.iter().map(|t| t.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["1102101", "1102102", "1102103"]);
+        assert_eq!(traces[2].level, 0);
+    }
+}
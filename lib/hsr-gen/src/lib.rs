@@ -1,4 +1,7 @@
 pub use mihomo_api;
 pub mod base;
+pub mod default;
 pub mod format;
 pub mod gen;
+pub mod rolls;
+pub mod style;
@@ -1,7 +1,7 @@
 use hsr_gen::{
     base::{get_base_image, BaseImage},
     format::ImageFormat,
-    gen::{generate, ScoreCounter},
+    gen::{generate, CardOptions, ScoreCounter},
 };
 use mihomo_api::api::Api;
 use tokio::fs::write;
@@ -23,6 +23,10 @@ async fn main() {
             ScoreCounter::EhrOnly,
             ScoreCounter::Hp,
             ScoreCounter::HpOnly,
+            ScoreCounter::Res,
+            ScoreCounter::Healing,
+            ScoreCounter::EnergyRegen,
+            ScoreCounter::CritOnly,
         ]
         .iter()
         .enumerate()
@@ -34,6 +38,7 @@ async fn main() {
                 ImageFormat::Png,
                 &lang,
                 &counter,
+                CardOptions::default(),
             )
             .await
             {
@@ -0,0 +1,145 @@
+//! Roll quality of relic substats, read from the `count` and `step` Mihomo reports.
+//!
+//! A substat rolls `count` times (the initial roll included) and every roll is
+//! low, mid or high, adding 0, 1 or 2 steps of an eighth of the low roll.
+
+use mihomo_api::character::{Relic, SubAffix};
+
+/// How well one substat rolled.
+#[derive(Clone, Debug)]
+pub struct SubstatRolls {
+    pub field: String,
+    pub name: String,
+    pub display: String,
+    /// Number of rolls, including the initial one.
+    pub rolls: u8,
+    /// Steps above the low roll, summed over every roll.
+    pub steps: u8,
+    /// Sum of the rolls as a percentage of `rolls` high rolls.
+    pub efficiency: f64,
+}
+
+impl SubstatRolls {
+    pub fn of(affix: &SubAffix) -> Self {
+        Self {
+            field: affix.field.clone(),
+            name: affix.name.clone(),
+            display: affix.display.clone(),
+            rolls: affix.count,
+            steps: affix.step,
+            efficiency: efficiency(affix.count as u32, affix.step as u32),
+        }
+    }
+    /// Tier of each roll from 1 (low) to 3 (high). Only the total is known, so
+    /// the steps are spread as evenly as possible.
+    pub fn tiers(&self) -> Vec<u8> {
+        let rolls = self.rolls.max(1);
+        let (base, extra) = (self.steps / rolls, self.steps % rolls);
+        (0..self.rolls)
+            .map(|i| (base + u8::from(i < extra)).min(2) + 1)
+            .collect()
+    }
+}
+
+/// Roll quality of a whole relic.
+#[derive(Clone, Debug)]
+pub struct RelicRolls {
+    /// Rolls of every substat, including the initial ones.
+    pub rolls: u32,
+    pub efficiency: f64,
+    pub substats: Vec<SubstatRolls>,
+}
+
+impl RelicRolls {
+    pub fn of(relic: &Relic) -> Self {
+        let substats = relic
+            .sub_affix
+            .iter()
+            .map(SubstatRolls::of)
+            .collect::<Vec<_>>();
+        let rolls = substats.iter().map(|s| s.rolls as u32).sum();
+        let steps = substats.iter().map(|s| s.steps as u32).sum();
+        Self {
+            rolls,
+            efficiency: efficiency(rolls, steps),
+            substats,
+        }
+    }
+}
+
+fn efficiency(rolls: u32, steps: u32) -> f64 {
+    if rolls == 0 {
+        return 0.0;
+    }
+    (8 * rolls + steps) as f64 / (10 * rolls) as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use mihomo_api::character::Properties;
+
+    use super::*;
+
+    fn affix(count: u8, step: u8) -> SubAffix {
+        SubAffix {
+            r#type: String::new(),
+            field: "crit_rate".to_string(),
+            name: String::new(),
+            icon: String::new(),
+            value: 0.0,
+            display: String::new(),
+            percent: true,
+            count,
+            step,
+        }
+    }
+
+    fn substat(rolls: u8, steps: u8) -> SubstatRolls {
+        SubstatRolls::of(&affix(rolls, steps))
+    }
+
+    #[test]
+    fn efficiency_spans_low_to_high_rolls() {
+        assert_eq!(efficiency(0, 0), 0.0);
+        assert_eq!(efficiency(4, 0), 80.0);
+        assert_eq!(efficiency(4, 4), 90.0);
+        assert_eq!(efficiency(4, 8), 100.0);
+    }
+
+    #[test]
+    fn tiers_spread_steps_evenly() {
+        assert_eq!(substat(3, 0).tiers(), [1, 1, 1]);
+        assert_eq!(substat(3, 4).tiers(), [3, 2, 2]);
+        assert_eq!(substat(2, 4).tiers(), [3, 3]);
+        assert_eq!(substat(1, 1).tiers(), [2]);
+        assert!(substat(0, 0).tiers().is_empty());
+    }
+
+    #[test]
+    fn relic_efficiency_weighs_every_roll() {
+        let relic = Relic {
+            main_affix: Properties {
+                r#type: String::new(),
+                field: "hp".to_string(),
+                name: String::new(),
+                icon: String::new(),
+                value: 705.6,
+                display: String::new(),
+                percent: false,
+            },
+            sub_affix: vec![affix(1, 0), affix(3, 6), affix(2, 2), affix(2, 0)],
+            id: String::new(),
+            name: String::new(),
+            set_id: String::new(),
+            set_name: String::new(),
+            rarity: 5,
+            level: 15,
+            icon: String::new(),
+        };
+        let rolls = RelicRolls::of(&relic);
+        assert_eq!(rolls.rolls, 8);
+        assert_eq!(rolls.efficiency, efficiency(8, 8));
+        assert_eq!(rolls.substats[1].efficiency, 100.0);
+        assert_eq!(rolls.substats[0].efficiency, 80.0);
+    }
+}
@@ -0,0 +1,69 @@
+//! Selectable card designs, picked with [`CardStyle`].
+
+use std::{fmt::Display, str::FromStr};
+
+use async_trait::async_trait;
+use gen_utils::style::{parse_style, CardRenderer, Styles};
+use image::DynamicImage;
+use mihomo_api::{api::Api, character::Character};
+
+use crate::gen::{render_classic, CardOptions, ScoreCounter};
+
+/// Everything a card design needs to draw one character.
+pub struct CardInput<'a> {
+    pub api: &'a Api,
+    pub character: &'a Character,
+    /// Background chosen by the caller. Styles may crop or ignore it.
+    pub base_image: DynamicImage,
+    pub lang: &'a str,
+    pub counter: &'a ScoreCounter,
+    pub options: CardOptions,
+}
+
+/// The original landscape card.
+pub struct Classic;
+
+#[async_trait]
+impl<'a> CardRenderer<CardInput<'a>> for Classic {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+    async fn render(&self, input: CardInput<'a>) -> Option<DynamicImage> {
+        render_classic(input).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardStyle {
+    #[default]
+    Classic,
+}
+
+impl CardStyle {
+    pub fn renderer(&self) -> &'static dyn for<'a> CardRenderer<CardInput<'a>> {
+        match self {
+            CardStyle::Classic => &Classic,
+        }
+    }
+}
+
+impl Styles for CardStyle {
+    const ALL: &'static [Self] = &[CardStyle::Classic];
+    fn name(&self) -> &'static str {
+        self.renderer().name()
+    }
+}
+
+impl Display for CardStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CardStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_style(s)
+    }
+}
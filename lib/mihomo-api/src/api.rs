@@ -1,19 +1,32 @@
-use std::time::SystemTime;
+use std::{collections::BTreeMap, time::SystemTime};
 
 use crate::{
-    cache::Cache,
-    store::Store,
+    cache::{default_cache, Cache, CacheBackend, CacheStats, EvictionPolicy, SingleFlight, Usage},
+    error::MihomoError,
+    store::{parse_character_names, Store},
     user::{ApiRawUser, ApiUser},
 };
 use image::{load_from_memory, DynamicImage};
-use reqwest::{Client, Error as ReqwestError};
+use reqwest::{header::RETRY_AFTER, Client, Url};
+
+use cache_backend::{parse_retry_after, RateLimiter};
+pub use cache_backend::{RateLimit, RetryPolicy};
 
 pub struct Api {
     pub client: Client,
     cache: Cache,
     store: Store,
+    mihomo_url: String,
+    star_rail_res_url: String,
+    inflight: SingleFlight<(i32, String), Result<ApiRawUser, MihomoError>>,
+    limiter: RateLimiter,
+    asset_limiter: RateLimiter,
+    retry: RetryPolicy,
 }
 
+pub const DEFAULT_MIHOMO_URL: &str = "https://api.mihomo.me";
+pub const DEFAULT_STAR_RAIL_RES_URL: &str =
+    "https://raw.githubusercontent.com/Mar-7th/StarRailRes/master";
 const USER_AGENT: &str = "MihoyoApi/0.1.0 (+https://artifacter.neody.land/)";
 
 impl Default for Api {
@@ -24,14 +37,14 @@ impl Default for Api {
 
 impl Api {
     pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .user_agent(USER_AGENT)
-                .build()
-                .expect("Failed to build reqwest client"),
-            cache: Cache::new(),
-            store: Store::new(),
-        }
+        Self::builder().build()
+    }
+    /// Builds an `Api` that stores its cache in `backend` instead of the default directory.
+    pub fn with_cache(backend: impl CacheBackend + 'static) -> Self {
+        Self::builder().cache(backend).build()
+    }
+    pub fn builder() -> ApiBuilder {
+        ApiBuilder::default()
     }
     pub async fn asset(
         &self,
@@ -41,33 +54,73 @@ impl Api {
         match self.cache.get(&cache_uri).await {
             Ok((buf, _)) => Ok(load_from_memory(&buf)?),
             Err(_) => {
-                let uri = format!(
-                    "https://raw.githubusercontent.com/Mar-7th/StarRailRes/master/{}",
-                    uri
-                );
-                let data = self.request(&uri).await?;
+                let uri = format!("{}/{}", self.star_rail_res_url, uri);
+                let data = self.request(&self.asset_limiter, &uri).await?;
                 let _ = self.cache.set(cache_uri, &data, SystemTime::now()).await;
                 Ok(load_from_memory(&data)?)
             }
         }
     }
-    async fn request(&self, uri: &str) -> Result<Vec<u8>, ReqwestError> {
-        log::info!("requesting {}", uri);
-        let request = self.client.get(uri);
-        let body = request.send().await?;
-        let body = body.error_for_status()?;
-        let body = body.bytes().await?;
-        Ok(body.to_vec())
+    pub async fn cache_stats(&self) -> std::io::Result<CacheStats> {
+        self.cache.stats().await
+    }
+    /// Evicts cache entries violating `policy`, returning what was removed.
+    pub async fn sweep_cache(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        self.cache.sweep(policy).await
+    }
+    /// Sends a GET through `limiter`, retrying per the retry policy.
+    async fn request(&self, limiter: &RateLimiter, uri: &str) -> Result<Vec<u8>, MihomoError> {
+        let host = Url::parse(uri)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+        let mut attempt = 0;
+        loop {
+            if !limiter.acquire(&host).await {
+                log::warn!("rate limit reached for {}", host);
+                return Err(MihomoError::RateLimited { retry_after: None });
+            }
+            log::info!("requesting {}", uri);
+            let request = self.client.get(uri);
+            let body = request.send().await?;
+            let status = body.status();
+            if status.is_client_error() || status.is_server_error() {
+                let retry_after = body
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(parse_retry_after);
+                if RetryPolicy::is_retryable(status.as_u16()) {
+                    if let Some(delay) = self.retry.delay(attempt, retry_after) {
+                        log::warn!("{} returned {}, retrying in {:?}", uri, status, delay);
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                }
+                return Err(MihomoError::from_status(
+                    status.as_u16(),
+                    retry_after.map(|d| d.as_secs()),
+                ));
+            }
+            let body = body.bytes().await?;
+            return Ok(body.to_vec());
+        }
     }
     pub fn get_store(&self) -> &Store {
         &self.store
     }
-    async fn fetch_user(&self, uid: i32, lang: String) -> Result<ApiRawUser, Option<ReqwestError>> {
+    /// English names of every playable character by id, from the StarRailRes index.
+    pub async fn character_names(&self) -> Result<BTreeMap<u32, String>, MihomoError> {
+        let uri = format!("{}/index_new/en/characters.json", self.star_rail_res_url);
+        parse_character_names(&self.request(&self.asset_limiter, &uri).await?)
+    }
+    async fn fetch_user(&self, uid: i32, lang: String) -> Result<ApiRawUser, MihomoError> {
         let contents = self
-            .request(&format!(
-                "https://api.mihomo.me/sr_info_parsed/{}?lang={}",
-                uid, lang
-            ))
+            .request(
+                &self.limiter,
+                &format!("{}/sr_info_parsed/{}?lang={}", self.mihomo_url, uid, lang),
+            )
             .await?;
         let lastupdate = SystemTime::now();
         Ok(ApiRawUser::from_raw(contents, uid, lastupdate))
@@ -82,6 +135,16 @@ impl Api {
             )
             .await
     }
+    /// Fetches and caches the user, sharing one upstream request among concurrent callers.
+    async fn fetch_and_cache(&self, uid: i32, lang: String) -> Result<ApiRawUser, MihomoError> {
+        self.inflight
+            .run((uid, lang.clone()), || async {
+                let raw = self.fetch_user(uid, lang.clone()).await?;
+                let _ = self.set_cache(&raw, lang).await;
+                Ok(raw)
+            })
+            .await
+    }
     async fn find_cache(&self, uid: i32, lang: String) -> Option<ApiRawUser> {
         let (buf, modtime) = self
             .cache
@@ -90,7 +153,9 @@ impl Api {
             .ok()?;
         Some(ApiRawUser::from_raw(buf.to_vec(), uid, modtime))
     }
-    pub async fn simple(&self, uid: i32, lang: String) -> Result<(ApiUser, bool), String> {
+    /// Returns the user and whether it was served from the cache because Mihomo is
+    /// rate limiting us or under maintenance.
+    pub async fn simple(&self, uid: i32, lang: String) -> Result<(ApiUser, bool), MihomoError> {
         let lang = match lang.to_lowercase().as_str() {
             "ja" | "ja-jp" | "jp" => "jp",
             "en" | "en-us" | "en-gb" => "en",
@@ -104,46 +169,98 @@ impl Api {
                         Some(new) => Ok((new, false)),
                         None => Ok((data, false)),
                     },
-                    Err(e) => {
-                        if e.contains("424") {
-                            Ok((data, true))
-                        } else {
-                            Err(e)
-                        }
+                    Err(e) if e.is_temporary() => {
+                        log::warn!("serving stale user {}: {}", uid, e);
+                        Ok((data, true))
                     }
+                    Err(e) => Err(e),
                 }
             }
             None => {
-                let userdata = self.fetch_user(uid, lang.to_string()).await;
-                match userdata {
-                    Ok(userdata) => {
-                        let _ = self.set_cache(&userdata, lang.to_string()).await;
-                        Ok((userdata.resolve()?, false))
-                    }
-                    Err(e) => Err(match e {
-                        Some(e) => format!("{}", e),
-                        None => String::from("unknown error"),
-                    }),
-                }
+                let userdata = self.fetch_and_cache(uid, lang.to_string()).await?;
+                Ok((userdata.resolve()?, false))
             }
         }
     }
-    async fn reload(&self, data: &ApiUser, lang: String) -> Result<Option<ApiUser>, String> {
+    async fn reload(&self, data: &ApiUser, lang: String) -> Result<Option<ApiUser>, MihomoError> {
         let lastupdate = SystemTime::now();
         if data.reload_time() >= lastupdate {
             Ok(None)
         } else {
-            let raw = self.fetch_user(data.uid(), lang.to_string()).await;
-            match raw {
-                Ok(raw) => {
-                    let _ = self.set_cache(&raw, lang).await;
-                    Ok(Some(raw.resolve()?))
-                }
-                Err(e) => Err(match e {
-                    Some(e) => format!("{}", e),
-                    None => String::from("unknown error"),
-                }),
-            }
+            let raw = self.fetch_and_cache(data.uid(), lang).await?;
+            Ok(Some(raw.resolve()?))
+        }
+    }
+}
+
+pub struct ApiBuilder {
+    cache: Option<Cache>,
+    mihomo_url: String,
+    star_rail_res_url: String,
+    rate_limit: RateLimit,
+    asset_rate_limit: RateLimit,
+    retry: RetryPolicy,
+}
+
+impl Default for ApiBuilder {
+    fn default() -> Self {
+        Self {
+            cache: None,
+            mihomo_url: DEFAULT_MIHOMO_URL.to_string(),
+            star_rail_res_url: DEFAULT_STAR_RAIL_RES_URL.to_string(),
+            rate_limit: RateLimit::default(),
+            asset_rate_limit: RateLimit::assets(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl ApiBuilder {
+    pub fn cache(mut self, backend: impl CacheBackend + 'static) -> Self {
+        self.cache = Some(Cache::new(backend));
+        self
+    }
+    /// Base URL of the Mihomo API, without a trailing slash. Defaults to `DEFAULT_MIHOMO_URL`.
+    pub fn mihomo_url(mut self, url: impl Into<String>) -> Self {
+        self.mihomo_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// Base URL of StarRailRes assets, without a trailing slash. Defaults to `DEFAULT_STAR_RAIL_RES_URL`.
+    pub fn star_rail_res_url(mut self, url: impl Into<String>) -> Self {
+        self.star_rail_res_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// Token bucket applied to each upstream host for user requests. Cached data
+    /// is served as stale when it refuses.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = limit;
+        self
+    }
+    /// Token bucket for StarRailRes files. Defaults to `RateLimit::assets()`,
+    /// since one card fetches about 30 icons.
+    pub fn asset_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.asset_rate_limit = limit;
+        self
+    }
+    /// Retries for 429 and 5xx responses. Use `RetryPolicy::none()` to disable.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    pub fn build(self) -> Api {
+        Api {
+            client: Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("Failed to build reqwest client"),
+            cache: self.cache.unwrap_or_else(default_cache),
+            store: Store::new(),
+            mihomo_url: self.mihomo_url,
+            star_rail_res_url: self.star_rail_res_url,
+            inflight: SingleFlight::new(),
+            limiter: RateLimiter::new(self.rate_limit),
+            asset_limiter: RateLimiter::new(self.asset_rate_limit),
+            retry: self.retry,
         }
     }
 }
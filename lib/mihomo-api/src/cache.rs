@@ -1,44 +1,11 @@
-use filetime::FileTime;
-use std::{path::Path, time::SystemTime};
-use tokio::{
-    fs::{create_dir_all, try_exists, write, File},
-    io::AsyncReadExt,
+#[cfg(feature = "sqlite")]
+pub use cache_backend::SqliteCache;
+pub use cache_backend::{
+    Cache, CacheBackend, CacheStats, EvictionPolicy, FsCache, MemoryCache, SingleFlight, Usage,
 };
 
 const CACHE_DIR: &str = "./hsr-cache";
 
-pub struct Cache {}
-
-impl Cache {
-    pub(crate) fn new() -> Self {
-        Self {}
-    }
-    pub(crate) async fn set(
-        &self,
-        key: String,
-        value: &[u8],
-        time: SystemTime,
-    ) -> std::io::Result<()> {
-        let path = Path::new(CACHE_DIR).join(key);
-        if let Some(p) = path.parent() {
-            if let Ok(false) = try_exists(p).await {
-                create_dir_all(p).await?;
-            }
-        }
-        write(&path, value).await?;
-        filetime::set_file_mtime(&path, FileTime::from_system_time(time))?;
-        Ok(())
-    }
-    pub(crate) async fn get(
-        &self,
-        path: impl AsRef<str>,
-    ) -> std::io::Result<(Vec<u8>, SystemTime)> {
-        let path = Path::new(CACHE_DIR).join(path.as_ref());
-        let mut f = File::open(path).await?;
-        let meta = f.metadata().await?;
-        let modtime = meta.modified()?;
-        let mut buf = Vec::with_capacity(meta.len() as usize);
-        f.read_to_end(&mut buf).await?;
-        Ok((buf, modtime))
-    }
+pub(crate) fn default_cache() -> Cache {
+    Cache::new(FsCache::new(CACHE_DIR))
 }
@@ -0,0 +1,107 @@
+use std::fmt::Display;
+
+#[derive(Clone, Debug)]
+pub enum MihomoError {
+    /// The UID is malformed or was rejected by Mihomo (400).
+    InvalidUid,
+    /// No player exists with the given UID (404).
+    NotFound,
+    /// Mihomo or the local limiter is rate limiting us (429). `retry_after` is in seconds.
+    RateLimited { retry_after: Option<u64> },
+    /// The game server is under maintenance or Mihomo cannot reach it (424).
+    Maintenance,
+    /// Any other upstream status.
+    UpstreamHttp(u16),
+    /// No response was received at all.
+    Transport(String),
+    /// The upstream payload or an asset could not be decoded.
+    Decode(String),
+}
+
+impl MihomoError {
+    pub(crate) fn from_status(status: u16, retry_after: Option<u64>) -> Self {
+        match status {
+            400 => Self::InvalidUid,
+            404 => Self::NotFound,
+            424 => Self::Maintenance,
+            429 => Self::RateLimited { retry_after },
+            s => Self::UpstreamHttp(s),
+        }
+    }
+    /// Whether a cached profile should be served as stale instead of failing.
+    pub fn is_temporary(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Maintenance)
+    }
+}
+
+impl Display for MihomoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidUid => write!(f, "invalid uid"),
+            Self::NotFound => write!(f, "player not found"),
+            Self::RateLimited {
+                retry_after: Some(s),
+            } => write!(f, "rate limited, retry after {}s", s),
+            Self::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            Self::Maintenance => write!(f, "game server is under maintenance"),
+            Self::UpstreamHttp(s) => write!(f, "upstream returned status {}", s),
+            Self::Transport(e) => write!(f, "upstream is unreachable: {}", e),
+            Self::Decode(e) => write!(f, "failed to decode: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MihomoError {}
+
+impl From<reqwest::Error> for MihomoError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(s) => Self::from_status(s.as_u16(), None),
+            None if e.is_decode() => Self::Decode(format!("{}", e)),
+            None => Self::Transport(format!("{}", e)),
+        }
+    }
+}
+
+impl From<image::ImageError> for MihomoError {
+    fn from(e: image::ImageError) -> Self {
+        Self::Decode(format!("{}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_map_to_variants() {
+        assert!(matches!(
+            MihomoError::from_status(400, None),
+            MihomoError::InvalidUid
+        ));
+        assert!(matches!(
+            MihomoError::from_status(404, None),
+            MihomoError::NotFound
+        ));
+        assert!(matches!(
+            MihomoError::from_status(429, Some(3)),
+            MihomoError::RateLimited {
+                retry_after: Some(3)
+            }
+        ));
+        assert!(matches!(
+            MihomoError::from_status(502, None),
+            MihomoError::UpstreamHttp(502)
+        ));
+    }
+
+    #[test]
+    fn only_throttling_falls_back_to_the_cache() {
+        assert!(MihomoError::from_status(424, None).is_temporary());
+        assert!(MihomoError::from_status(429, None).is_temporary());
+        assert!(!MihomoError::from_status(404, None).is_temporary());
+        assert!(!MihomoError::from_status(500, None).is_temporary());
+        // a UID that happens to contain 424 or 429 is not a throttling error
+        assert!(!MihomoError::Decode("uid 804290424".to_string()).is_temporary());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod api;
 pub mod cache;
 pub mod character;
+pub mod error;
 pub mod store;
 pub mod user;
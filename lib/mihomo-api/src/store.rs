@@ -1,3 +1,9 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::MihomoError;
+
 pub struct Store {}
 
 impl Default for Store {
@@ -11,3 +17,38 @@ impl Store {
         Self {}
     }
 }
+
+#[derive(Deserialize)]
+struct IndexCharacter {
+    name: String,
+}
+
+/// Character names by id in a StarRailRes `characters.json` index.
+pub fn parse_character_names(data: &[u8]) -> Result<BTreeMap<u32, String>, MihomoError> {
+    let index: BTreeMap<String, IndexCharacter> = serde_json::from_slice(data)
+        .map_err(|e| MihomoError::Decode(format!("character index: {}", e)))?;
+    Ok(index
+        .into_iter()
+        .filter_map(|(id, c)| Some((id.parse().ok()?, c.name)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_character_index() {
+        let data = br#"{
+            "1001": {"id": "1001", "name": "March 7th", "rarity": 4},
+            "8001": {"id": "8001", "name": "{NICKNAME}", "rarity": 5}
+        }"#;
+        let names = parse_character_names(data).unwrap();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[&1001], "March 7th");
+        assert!(matches!(
+            parse_character_names(b"[]"),
+            Err(MihomoError::Decode(_))
+        ));
+    }
+}
@@ -3,8 +3,9 @@ use std::time::{Duration, SystemTime};
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::character::Character;
+use crate::{character::Character, error::MihomoError};
 
+#[derive(Clone)]
 pub struct ApiRawUser {
     contents: Vec<u8>,
     uid: i32,
@@ -25,8 +26,9 @@ impl ApiRawUser {
     pub fn contents(&self) -> &[u8] {
         &self.contents
     }
-    pub fn resolve(&self) -> Result<ApiUser, String> {
-        ApiUser::from_raw(&self.contents, self.uid, self.modtime).ok_or("invalid json".to_string())
+    pub fn resolve(&self) -> Result<ApiUser, MihomoError> {
+        ApiUser::from_raw(&self.contents, self.uid, self.modtime)
+            .ok_or(MihomoError::Decode("invalid json".to_string()))
     }
 }
 
@@ -0,0 +1,222 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    cache::{default_cache, Cache, CacheBackend, CacheStats, EvictionPolicy, Usage},
+    error::ZzzError,
+    store::Store,
+    user::{ApiRawUser, ApiUser},
+};
+use cache_backend::parse_retry_after;
+use image::{load_from_memory, DynamicImage};
+use reqwest::{header::RETRY_AFTER, Client};
+use tokio::sync::OnceCell;
+
+pub struct Api {
+    pub client: Client,
+    cache: Cache,
+    store: OnceCell<Store>,
+    enka_url: String,
+    store_url: String,
+}
+
+pub const DEFAULT_ENKA_URL: &str = "https://enka.network";
+pub const DEFAULT_STORE_URL: &str =
+    "https://raw.githubusercontent.com/EnkaNetwork/API-docs/master/store/zzz";
+const USER_AGENT: &str = "ZzzApi/0.1.0 (+https://artifacter.neody.land/)";
+const STORE_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+
+impl Default for Api {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Api {
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+    /// Builds an `Api` that stores its cache in `backend` instead of the default directory.
+    pub fn with_cache(backend: impl CacheBackend + 'static) -> Self {
+        Self::builder().cache(backend).build()
+    }
+    pub fn builder() -> ApiBuilder {
+        ApiBuilder::default()
+    }
+    pub async fn asset(
+        &self,
+        path: &str,
+    ) -> Result<DynamicImage, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.trim_start_matches('/');
+        let cache_uri = format!("ui/{}", path);
+        match self.cache.get(&cache_uri).await {
+            Ok((buf, _)) => Ok(load_from_memory(&buf)?),
+            Err(_) => {
+                let data = self.request(&format!("{}/{}", self.enka_url, path)).await?;
+                let _ = self.cache.set(cache_uri, &data, SystemTime::now()).await;
+                Ok(load_from_memory(&data)?)
+            }
+        }
+    }
+    pub async fn cache_stats(&self) -> std::io::Result<CacheStats> {
+        self.cache.stats().await
+    }
+    /// Evicts cache entries violating `policy`, returning what was removed.
+    pub async fn sweep_cache(&self, policy: &EvictionPolicy) -> std::io::Result<Usage> {
+        self.cache.sweep(policy).await
+    }
+    async fn request(&self, uri: &str) -> Result<Vec<u8>, ZzzError> {
+        log::info!("requesting {}", uri);
+        let request = self.client.get(uri);
+        let body = request.send().await?;
+        let status = body.status();
+        if status.is_client_error() || status.is_server_error() {
+            let retry_after = body
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|h| h.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(ZzzError::from_status(
+                status.as_u16(),
+                retry_after.map(|d| d.as_secs()),
+            ));
+        }
+        let body = body.bytes().await?;
+        Ok(body.to_vec())
+    }
+    async fn store_file(&self, name: &str) -> Result<Vec<u8>, ZzzError> {
+        let key = format!("store/{}", name);
+        let cached = self.cache.get(&key).await.ok();
+        if let Some((buf, modtime)) = &cached {
+            if modtime.elapsed().unwrap_or_default() < STORE_TTL {
+                return Ok(buf.clone());
+            }
+        }
+        match self.request(&format!("{}/{}", self.store_url, name)).await {
+            Ok(data) => {
+                let _ = self.cache.set(key, &data, SystemTime::now()).await;
+                Ok(data)
+            }
+            Err(e) => match cached {
+                Some((buf, _)) => {
+                    log::warn!("failed to refresh {}, using stale copy: {}", name, e);
+                    Ok(buf)
+                }
+                None => Err(e),
+            },
+        }
+    }
+    pub async fn get_store(&self) -> Result<&Store, ZzzError> {
+        self.store
+            .get_or_try_init(|| async {
+                Store::from_raw(
+                    &self.store_file("avatars.json").await?,
+                    &self.store_file("weapons.json").await?,
+                    &self.store_file("equipments.json").await?,
+                    &self.store_file("locs.json").await?,
+                )
+                .map_err(ZzzError::Decode)
+            })
+            .await
+    }
+    async fn fetch_user(&self, uid: i32) -> Result<ApiRawUser, ZzzError> {
+        let contents = self
+            .request(&format!("{}/api/zzz/uid/{}", self.enka_url, uid))
+            .await?;
+        let lastupdate = SystemTime::now();
+        Ok(ApiRawUser::from_raw(contents, uid, lastupdate))
+    }
+    async fn set_cache(&self, data: &ApiRawUser) -> std::io::Result<()> {
+        let now = SystemTime::now();
+        self.cache
+            .set(format!("user/{}", data.uid()), data.contents(), now)
+            .await
+    }
+    async fn find_cache(&self, uid: i32) -> Option<ApiRawUser> {
+        let (buf, modtime) = self.cache.get(format!("user/{}", uid)).await.ok()?;
+        Some(ApiRawUser::from_raw(buf.to_vec(), uid, modtime))
+    }
+    /// Returns the user and whether it was served from the cache.
+    pub async fn simple(&self, uid: i32, lang: String) -> Result<(ApiUser, bool), ZzzError> {
+        let store = self.get_store().await?;
+        match self.find_cache(uid).await {
+            Some(cache) => {
+                let data = cache.resolve(store, &lang)?;
+                match self.reload(&data, store, &lang).await {
+                    Ok(u) => match u {
+                        Some(new) => Ok((new, false)),
+                        None => Ok((data, true)),
+                    },
+                    Err(e) if e.is_temporary() => {
+                        log::warn!("serving stale user {}: {}", uid, e);
+                        Ok((data, true))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            None => {
+                let userdata = self.fetch_user(uid).await?;
+                let _ = self.set_cache(&userdata).await;
+                Ok((userdata.resolve(store, &lang)?, false))
+            }
+        }
+    }
+    async fn reload(
+        &self,
+        data: &ApiUser,
+        store: &Store,
+        lang: &str,
+    ) -> Result<Option<ApiUser>, ZzzError> {
+        if data.reload_time() >= SystemTime::now() {
+            Ok(None)
+        } else {
+            let raw = self.fetch_user(data.uid()).await?;
+            let _ = self.set_cache(&raw).await;
+            Ok(Some(raw.resolve(store, lang)?))
+        }
+    }
+}
+
+pub struct ApiBuilder {
+    cache: Option<Cache>,
+    enka_url: String,
+    store_url: String,
+}
+
+impl Default for ApiBuilder {
+    fn default() -> Self {
+        Self {
+            cache: None,
+            enka_url: DEFAULT_ENKA_URL.to_string(),
+            store_url: DEFAULT_STORE_URL.to_string(),
+        }
+    }
+}
+
+impl ApiBuilder {
+    pub fn cache(mut self, backend: impl CacheBackend + 'static) -> Self {
+        self.cache = Some(Cache::new(backend));
+        self
+    }
+    /// Base URL of Enka.Network, without a trailing slash. Defaults to `DEFAULT_ENKA_URL`.
+    pub fn enka_url(mut self, url: impl Into<String>) -> Self {
+        self.enka_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    /// Base URL of the ZZZ store files, without a trailing slash. Defaults to `DEFAULT_STORE_URL`.
+    pub fn store_url(mut self, url: impl Into<String>) -> Self {
+        self.store_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    pub fn build(self) -> Api {
+        Api {
+            client: Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("Failed to build reqwest client"),
+            cache: self.cache.unwrap_or_else(default_cache),
+            store: OnceCell::new(),
+            enka_url: self.enka_url,
+            store_url: self.store_url,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+#[cfg(feature = "sqlite")]
+pub use cache_backend::SqliteCache;
+pub use cache_backend::{
+    Cache, CacheBackend, CacheStats, EvictionPolicy, FsCache, MemoryCache, Usage,
+};
+
+const CACHE_DIR: &str = "./zzz-cache";
+
+pub(crate) fn default_cache() -> Cache {
+    Cache::new(FsCache::new(CACHE_DIR))
+}
@@ -0,0 +1,216 @@
+use serde::Deserialize;
+
+use crate::{
+    property::{Element, Property, StatValue},
+    store::Store,
+};
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawProperty {
+    pub property_id: u32,
+    pub property_value: f64,
+    pub property_level: u32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawEquipment {
+    pub id: u32,
+    pub level: u32,
+    pub break_level: u32,
+    pub main_property_list: Vec<RawProperty>,
+    pub random_property_list: Vec<RawProperty>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawEquipped {
+    pub slot: u8,
+    pub equipment: RawEquipment,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawWeapon {
+    pub id: u32,
+    pub level: u32,
+    pub break_level: u32,
+    pub upgrade_level: u32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawSkill {
+    pub level: u32,
+    pub index: u32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RawAvatar {
+    pub id: u32,
+    pub level: u32,
+    pub promotion_level: u32,
+    pub talent_level: u32,
+    pub core_skill_enhancement: u32,
+    pub weapon: Option<RawWeapon>,
+    pub skill_level_list: Vec<RawSkill>,
+    pub equipped_list: Vec<RawEquipped>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscRarity {
+    B,
+    A,
+    S,
+}
+
+impl DiscRarity {
+    fn from_digit(d: u32) -> Self {
+        match d {
+            4 => Self::S,
+            3 => Self::A,
+            _ => Self::B,
+        }
+    }
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Self::S => 15,
+            Self::A => 12,
+            Self::B => 9,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubStat {
+    pub value: StatValue,
+    /// Number of times this substat was rolled, including the initial roll.
+    pub rolls: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DriveDisc {
+    pub id: u32,
+    pub suit_id: u32,
+    pub suit_name: String,
+    pub suit_icon: String,
+    pub slot: u8,
+    pub rarity: DiscRarity,
+    pub level: u32,
+    pub main_stat: StatValue,
+    pub sub_stats: Vec<SubStat>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WEngine {
+    pub id: u32,
+    pub name: String,
+    pub rarity: u8,
+    pub level: u32,
+    pub refinement: u32,
+    pub icon: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Agent {
+    pub id: u32,
+    pub name: String,
+    pub rarity: u8,
+    pub level: u32,
+    pub promotion: u32,
+    pub mindscape: u32,
+    pub core_skill: u32,
+    /// Skill levels ordered by the in-game skill index.
+    pub skills: Vec<(u32, u32)>,
+    pub weapon: Option<WEngine>,
+    pub discs: Vec<DriveDisc>,
+    pub element: Option<Element>,
+    pub profession: String,
+    pub image: String,
+    pub accent_color: String,
+}
+
+impl RawAvatar {
+    pub fn resolve(&self, store: &Store, lang: &str) -> Agent {
+        let data = store.avatar(self.id).cloned().unwrap_or_default();
+        let weapon = self.weapon.as_ref().map(|w| {
+            let data = store.weapon(w.id).cloned().unwrap_or_default();
+            WEngine {
+                id: w.id,
+                name: store.locale(&data.item_name, lang),
+                rarity: data.rarity,
+                level: w.level,
+                refinement: w.upgrade_level,
+                icon: data.image_path,
+            }
+        });
+        let mut skills: Vec<(u32, u32)> = self
+            .skill_level_list
+            .iter()
+            .map(|s| (s.index, s.level))
+            .collect();
+        skills.sort_by_key(|s| s.0);
+        let mut discs: Vec<DriveDisc> = self
+            .equipped_list
+            .iter()
+            .map(|e| e.resolve(store, lang))
+            .collect();
+        discs.sort_by_key(|d| d.slot);
+        Agent {
+            id: self.id,
+            name: store.locale(&data.name, lang),
+            rarity: data.rarity,
+            level: self.level,
+            promotion: self.promotion_level,
+            mindscape: self.talent_level,
+            core_skill: self.core_skill_enhancement,
+            skills,
+            weapon,
+            discs,
+            element: data.element_types.first().and_then(Element::parse),
+            profession: data.profession_type,
+            image: data.image,
+            accent_color: data.colors.accent,
+        }
+    }
+}
+
+impl RawEquipped {
+    fn resolve(&self, store: &Store, lang: &str) -> DriveDisc {
+        let e = &self.equipment;
+        let suit_id = e.id / 100 * 100;
+        let rarity = DiscRarity::from_digit(e.id / 10 % 10);
+        let suit = store.suit(suit_id).cloned().unwrap_or_default();
+        let main_stat = match e.main_property_list.first() {
+            Some(p) => {
+                let scale = 1.0 + e.level as f64 * 3.0 / rarity.max_level() as f64;
+                StatValue::from_raw(p.property_id, (p.property_value * scale).floor())
+            }
+            None => StatValue(Property::Unknown(0), 0.0),
+        };
+        let sub_stats = e
+            .random_property_list
+            .iter()
+            .map(|p| SubStat {
+                value: StatValue::from_raw(
+                    p.property_id,
+                    p.property_value * p.property_level as f64,
+                ),
+                rolls: p.property_level,
+            })
+            .collect();
+        DriveDisc {
+            id: e.id,
+            suit_id,
+            suit_name: store.locale(&suit.name, lang),
+            suit_icon: suit.icon,
+            slot: self.slot,
+            rarity,
+            level: e.level,
+            main_stat,
+            sub_stats,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+#[derive(Clone, Debug)]
+pub enum ZzzError {
+    /// The UID is malformed or was rejected by Enka.Network (400).
+    InvalidUid,
+    /// No player exists with the given UID (404).
+    NotFound,
+    /// Enka.Network is rate limiting us (429). `retry_after` is in seconds.
+    RateLimited { retry_after: Option<u64> },
+    /// The game server is under maintenance or Enka.Network cannot reach it (424).
+    Maintenance,
+    /// Any other upstream status.
+    UpstreamHttp(u16),
+    /// No response was received at all.
+    Transport(String),
+    /// The upstream payload, a store file or an asset could not be decoded.
+    Decode(String),
+}
+
+impl ZzzError {
+    pub(crate) fn from_status(status: u16, retry_after: Option<u64>) -> Self {
+        match status {
+            400 => Self::InvalidUid,
+            404 => Self::NotFound,
+            424 => Self::Maintenance,
+            429 => Self::RateLimited { retry_after },
+            s => Self::UpstreamHttp(s),
+        }
+    }
+    /// Whether a cached profile should be served as stale instead of failing.
+    pub fn is_temporary(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Maintenance)
+    }
+}
+
+impl Display for ZzzError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidUid => write!(f, "invalid uid"),
+            Self::NotFound => write!(f, "player not found"),
+            Self::RateLimited {
+                retry_after: Some(s),
+            } => write!(f, "rate limited, retry after {}s", s),
+            Self::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            Self::Maintenance => write!(f, "game server is under maintenance"),
+            Self::UpstreamHttp(s) => write!(f, "upstream returned status {}", s),
+            Self::Transport(e) => write!(f, "upstream is unreachable: {}", e),
+            Self::Decode(e) => write!(f, "failed to decode: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ZzzError {}
+
+impl From<reqwest::Error> for ZzzError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(s) => Self::from_status(s.as_u16(), None),
+            None if e.is_decode() => Self::Decode(format!("{}", e)),
+            None => Self::Transport(format!("{}", e)),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ZzzError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Decode(format!("{}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_map_to_variants() {
+        assert!(matches!(
+            ZzzError::from_status(400, None),
+            ZzzError::InvalidUid
+        ));
+        assert!(matches!(
+            ZzzError::from_status(404, None),
+            ZzzError::NotFound
+        ));
+        assert!(matches!(
+            ZzzError::from_status(429, Some(3)),
+            ZzzError::RateLimited {
+                retry_after: Some(3)
+            }
+        ));
+        assert!(matches!(
+            ZzzError::from_status(503, None),
+            ZzzError::UpstreamHttp(503)
+        ));
+    }
+
+    #[test]
+    fn only_throttling_falls_back_to_the_cache() {
+        assert!(ZzzError::from_status(424, None).is_temporary());
+        assert!(ZzzError::from_status(429, None).is_temporary());
+        assert!(!ZzzError::from_status(400, None).is_temporary());
+        // a UID that happens to contain 424 is not a maintenance error
+        assert!(!ZzzError::Decode("uid 1424242424".to_string()).is_temporary());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod api;
+pub mod cache;
+pub mod character;
+pub mod error;
+pub mod property;
+pub mod store;
+pub mod user;
@@ -0,0 +1,183 @@
+use std::fmt::Display;
+
+#[derive(Hash, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Element {
+    Physical,
+    Fire,
+    Ice,
+    Electric,
+    Ether,
+}
+
+impl Element {
+    pub fn parse(s: impl AsRef<str>) -> Option<Self> {
+        Some(match s.as_ref() {
+            "Physics" | "Physical" => Self::Physical,
+            "Fire" => Self::Fire,
+            "Ice" | "FireFrost" => Self::Ice,
+            "Elec" | "Electric" => Self::Electric,
+            "Ether" => Self::Ether,
+            _ => return None,
+        })
+    }
+    pub fn color_rgb(&self) -> [u8; 3] {
+        match self {
+            Self::Physical => [240, 210, 60],
+            Self::Fire => [255, 90, 40],
+            Self::Ice => [90, 200, 255],
+            Self::Electric => [60, 140, 255],
+            Self::Ether => [255, 70, 160],
+        }
+    }
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Hash, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Property {
+    Hp,
+    HpPercent,
+    Attack,
+    AttackPercent,
+    Defense,
+    DefensePercent,
+    Impact,
+    ImpactPercent,
+    CritRate,
+    CritDmg,
+    PenRatio,
+    Pen,
+    EnergyRegen,
+    EnergyRegenPercent,
+    AnomalyProficiency,
+    AnomalyMastery,
+    AnomalyMasteryPercent,
+    DamageBonus(Element),
+    Unknown(u32),
+}
+
+impl Property {
+    pub fn from_id(id: u32) -> Self {
+        match id {
+            11101 | 11103 => Self::Hp,
+            11102 => Self::HpPercent,
+            12101 | 12103 => Self::Attack,
+            12102 => Self::AttackPercent,
+            13101 | 13103 => Self::Defense,
+            13102 => Self::DefensePercent,
+            12201 | 12203 => Self::Impact,
+            12202 => Self::ImpactPercent,
+            20101 | 20103 => Self::CritRate,
+            21101 | 21103 => Self::CritDmg,
+            23101 | 23103 => Self::PenRatio,
+            23201 | 23203 => Self::Pen,
+            30501 | 30503 => Self::EnergyRegen,
+            30502 => Self::EnergyRegenPercent,
+            31201 | 31203 => Self::AnomalyProficiency,
+            31401 | 31403 => Self::AnomalyMastery,
+            31402 => Self::AnomalyMasteryPercent,
+            31501 | 31503 => Self::DamageBonus(Element::Physical),
+            31601 | 31603 => Self::DamageBonus(Element::Fire),
+            31701 | 31703 => Self::DamageBonus(Element::Ice),
+            31801 | 31803 => Self::DamageBonus(Element::Electric),
+            31901 | 31903 => Self::DamageBonus(Element::Ether),
+            _ => Self::Unknown(id),
+        }
+    }
+    pub fn is_percent(&self) -> bool {
+        matches!(
+            self,
+            Self::HpPercent
+                | Self::AttackPercent
+                | Self::DefensePercent
+                | Self::ImpactPercent
+                | Self::CritRate
+                | Self::CritDmg
+                | Self::PenRatio
+                | Self::EnergyRegenPercent
+                | Self::AnomalyMasteryPercent
+                | Self::DamageBonus(_)
+        )
+    }
+    /// Converts the raw value stored by the game (basis points for percentages) into the displayed value.
+    pub fn value(&self, raw: f64) -> f64 {
+        match self {
+            Self::EnergyRegen => raw / 100.0,
+            p if p.is_percent() => raw / 100.0,
+            _ => raw,
+        }
+    }
+    pub fn en(&self) -> &str {
+        match self {
+            Self::Hp | Self::HpPercent => "HP",
+            Self::Attack | Self::AttackPercent => "ATK",
+            Self::Defense | Self::DefensePercent => "DEF",
+            Self::Impact | Self::ImpactPercent => "Impact",
+            Self::CritRate => "CRIT Rate",
+            Self::CritDmg => "CRIT DMG",
+            Self::PenRatio => "PEN Ratio",
+            Self::Pen => "PEN",
+            Self::EnergyRegen | Self::EnergyRegenPercent => "Energy Regen",
+            Self::AnomalyProficiency => "Anomaly Proficiency",
+            Self::AnomalyMastery | Self::AnomalyMasteryPercent => "Anomaly Mastery",
+            Self::DamageBonus(Element::Physical) => "Physical DMG Bonus",
+            Self::DamageBonus(Element::Fire) => "Fire DMG Bonus",
+            Self::DamageBonus(Element::Ice) => "Ice DMG Bonus",
+            Self::DamageBonus(Element::Electric) => "Electric DMG Bonus",
+            Self::DamageBonus(Element::Ether) => "Ether DMG Bonus",
+            Self::Unknown(_) => "Unknown",
+        }
+    }
+    pub fn ja(&self) -> &str {
+        match self {
+            Self::Hp | Self::HpPercent => "HP",
+            Self::Attack | Self::AttackPercent => "攻撃力",
+            Self::Defense | Self::DefensePercent => "防御力",
+            Self::Impact | Self::ImpactPercent => "衝撃力",
+            Self::CritRate => "会心率",
+            Self::CritDmg => "会心ダメージ",
+            Self::PenRatio => "貫通率",
+            Self::Pen => "貫通値",
+            Self::EnergyRegen | Self::EnergyRegenPercent => "エネルギー自動回復",
+            Self::AnomalyProficiency => "異常マスタリー",
+            Self::AnomalyMastery | Self::AnomalyMasteryPercent => "異常掌握",
+            Self::DamageBonus(Element::Physical) => "物理属性ダメージ",
+            Self::DamageBonus(Element::Fire) => "炎属性ダメージ",
+            Self::DamageBonus(Element::Ice) => "氷属性ダメージ",
+            Self::DamageBonus(Element::Electric) => "電気属性ダメージ",
+            Self::DamageBonus(Element::Ether) => "エーテル属性ダメージ",
+            Self::Unknown(_) => "不明",
+        }
+    }
+    pub fn to_string_locale(&self, lang: &str) -> String {
+        match lang {
+            "ja-JP" | "ja" => self.ja(),
+            _ => self.en(),
+        }
+        .to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct StatValue(pub Property, pub f64);
+
+impl StatValue {
+    pub fn from_raw(id: u32, raw: f64) -> Self {
+        let property = Property::from_id(id);
+        Self(property, property.value(raw))
+    }
+}
+
+impl Display for StatValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            Property::EnergyRegen => write!(f, "{:.2}", self.1),
+            p if p.is_percent() => write!(f, "{:.1}%", self.1),
+            _ => write!(f, "{}", self.1.round() as u64),
+        }
+    }
+}
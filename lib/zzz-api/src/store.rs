@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct AvatarColors {
+    pub accent: String,
+    pub mindscape: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct AvatarData {
+    pub name: String,
+    pub rarity: u8,
+    pub profession_type: String,
+    pub element_types: Vec<String>,
+    pub image: String,
+    pub circle_icon: String,
+    pub colors: AvatarColors,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WeaponStat {
+    pub property_id: u32,
+    pub property_value: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WeaponData {
+    pub item_name: String,
+    pub rarity: u8,
+    pub image_path: String,
+    pub main_stat: WeaponStat,
+    pub secondary_stat: WeaponStat,
+    pub profession_type: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct SuitData {
+    pub name: String,
+    pub icon: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Equipments {
+    pub suits: HashMap<String, SuitData>,
+}
+
+pub struct Store {
+    pub avatars: HashMap<String, AvatarData>,
+    pub weapons: HashMap<String, WeaponData>,
+    pub suits: HashMap<String, SuitData>,
+    pub locs: HashMap<String, HashMap<String, String>>,
+}
+
+impl Store {
+    pub(crate) fn from_raw(
+        avatars: &[u8],
+        weapons: &[u8],
+        equipments: &[u8],
+        locs: &[u8],
+    ) -> Result<Self, String> {
+        let equipments: Equipments =
+            serde_json::from_slice(equipments).map_err(|e| format!("equipments: {}", e))?;
+        Ok(Self {
+            avatars: serde_json::from_slice(avatars).map_err(|e| format!("avatars: {}", e))?,
+            weapons: serde_json::from_slice(weapons).map_err(|e| format!("weapons: {}", e))?,
+            suits: equipments.suits,
+            locs: serde_json::from_slice(locs).map_err(|e| format!("locs: {}", e))?,
+        })
+    }
+    pub fn locale(&self, key: impl AsRef<str>, lang: impl AsRef<str>) -> String {
+        let key = key.as_ref();
+        let lang = match lang.as_ref() {
+            "ja" | "ja-JP" | "jp" => "ja",
+            "en" | "en-US" | "en-GB" => "en",
+            lang => lang,
+        };
+        self.locs
+            .get(lang)
+            .or_else(|| self.locs.get("en"))
+            .and_then(|l| l.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+    pub fn avatar(&self, id: u32) -> Option<&AvatarData> {
+        self.avatars.get(&id.to_string())
+    }
+    pub fn weapon(&self, id: u32) -> Option<&WeaponData> {
+        self.weapons.get(&id.to_string())
+    }
+    pub fn suit(&self, id: u32) -> Option<&SuitData> {
+        self.suits.get(&id.to_string())
+    }
+}
@@ -0,0 +1,105 @@
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use crate::{
+    character::{Agent, RawAvatar},
+    error::ZzzError,
+    store::Store,
+};
+
+pub struct ApiRawUser {
+    contents: Vec<u8>,
+    uid: i32,
+    modtime: SystemTime,
+}
+
+impl ApiRawUser {
+    pub fn from_raw(buf: Vec<u8>, uid: i32, modtime: SystemTime) -> Self {
+        Self {
+            contents: buf,
+            uid,
+            modtime,
+        }
+    }
+    pub fn uid(&self) -> i32 {
+        self.uid
+    }
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+    pub fn resolve(&self, store: &Store, lang: &str) -> Result<ApiUser, ZzzError> {
+        let raw: RawUser = serde_json::from_slice(&self.contents)?;
+        let profile = raw.player_info.social_detail.profile_detail;
+        Ok(ApiUser {
+            agents: raw
+                .player_info
+                .showcase_detail
+                .avatar_list
+                .iter()
+                .map(|a| a.resolve(store, lang))
+                .collect(),
+            uid: self.uid,
+            name: profile.nickname,
+            level: profile.level,
+            description: raw.player_info.social_detail.desc,
+            lastupdate: self.modtime,
+            ttl: raw.ttl,
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct RawProfileDetail {
+    nickname: String,
+    level: u32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct RawSocialDetail {
+    profile_detail: RawProfileDetail,
+    desc: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct RawShowcaseDetail {
+    avatar_list: Vec<RawAvatar>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct RawPlayerInfo {
+    showcase_detail: RawShowcaseDetail,
+    social_detail: RawSocialDetail,
+}
+
+#[derive(Deserialize)]
+struct RawUser {
+    #[serde(rename = "PlayerInfo")]
+    player_info: RawPlayerInfo,
+    #[serde(default)]
+    ttl: u64,
+}
+
+#[derive(Debug)]
+pub struct ApiUser {
+    pub agents: Vec<Agent>,
+    pub uid: i32,
+    pub name: String,
+    pub level: u32,
+    pub description: String,
+    pub lastupdate: SystemTime,
+    pub ttl: u64,
+}
+
+impl ApiUser {
+    pub fn uid(&self) -> i32 {
+        self.uid
+    }
+    pub fn reload_time(&self) -> SystemTime {
+        self.lastupdate + Duration::from_secs(self.ttl.max(30))
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    localization_build::set_root("./localization");
+    localization_build::set_default_locale("ja");
+}
@@ -0,0 +1,487 @@
+use std::{collections::HashMap, fmt::Display, io::Cursor, str::FromStr};
+
+use gen_utils::get_grade_image;
+use image::{
+    imageops::{overlay, resize, FilterType},
+    DynamicImage, ImageOutputFormat, Rgba, RgbaImage,
+};
+use imageproc::drawing::{draw_filled_circle_mut, draw_text_mut};
+use localization::t;
+use rusttype::{Font, Scale};
+use std::io::BufWriter;
+use zzz_api::{
+    api::Api,
+    character::{Agent, DriveDisc},
+    property::{Property, StatValue},
+};
+
+use crate::format::ImageFormat;
+use once_cell::sync::Lazy;
+
+static FONT: Lazy<Font> =
+    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../../assets/font.ttf")).unwrap());
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const GRAY: Rgba<u8> = Rgba([150, 150, 150, 255]);
+
+pub async fn generate(
+    api: &Api,
+    agent: &Agent,
+    format: ImageFormat,
+    lang: &str,
+    counter: &ScoreCounter,
+) -> Option<Vec<u8>> {
+    let lang = match lang {
+        "en" | "en-US" | "en-GB" => "en-US",
+        _ => "ja-JP",
+    };
+    let font = FONT.clone();
+    let accent = parse_color(&agent.accent_color)
+        .or_else(|| agent.element.map(|e| e.color_rgb()))
+        .unwrap_or([200, 200, 200]);
+    let mut base_image = background(accent);
+    // agent
+    if let Ok(img) = api.asset(&agent.image).await {
+        let img = resize(&img, 700, 700, FilterType::Triangle);
+        overlay(&mut base_image, &img, 0, 80);
+    }
+    draw_text_resized(
+        &mut base_image,
+        WHITE,
+        50,
+        50,
+        Scale::uniform(50.0),
+        &font,
+        &agent.name,
+        600,
+    );
+    let level = format!("Lv.{}/ M{}", agent.level, agent.mindscape);
+    draw_text_mut(
+        &mut base_image,
+        WHITE,
+        50,
+        110,
+        Scale::uniform(25.0),
+        &font,
+        &level,
+    );
+    // skills
+    for (index, (_, level)) in agent.skills.iter().enumerate() {
+        let level = format!("Lv.{}", level);
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            720,
+            100 + 60 * index as i32,
+            Scale::uniform(25.0),
+            &font,
+            &level,
+        );
+    }
+    // w-engine
+    if let Some(weapon) = &agent.weapon {
+        if let Ok(img) = api.asset(&weapon.icon).await {
+            let img = resize(&img, 150, 150, FilterType::Triangle);
+            overlay(&mut base_image, &img, 60, 800);
+        }
+        draw_text_resized(
+            &mut base_image,
+            WHITE,
+            250,
+            810,
+            Scale::uniform(40.0),
+            &font,
+            &weapon.name,
+            400,
+        );
+        let text = format!("Lv.{}/ R{}", weapon.level, weapon.refinement);
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            250,
+            870,
+            Scale::uniform(25.0),
+            &font,
+            &text,
+        );
+    }
+    // disc stat totals
+    for (index, stat) in sum_stats(&agent.discs).iter().enumerate() {
+        draw_text_resized(
+            &mut base_image,
+            WHITE,
+            850,
+            100 + 40 * index as i32,
+            Scale::uniform(25.0),
+            &font,
+            &stat.0.to_string_locale(lang),
+            200,
+        );
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            1100,
+            100 + 40 * index as i32,
+            Scale::uniform(25.0),
+            &font,
+            &stat.to_string(),
+        );
+    }
+    // suits
+    for (index, (name, count)) in count_suits(&agent.discs).iter().enumerate() {
+        draw_text_resized(
+            &mut base_image,
+            WHITE,
+            850,
+            560 + 40 * index as i32,
+            Scale::uniform(25.0),
+            &font,
+            name,
+            250,
+        );
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            1130,
+            560 + 40 * index as i32,
+            Scale::uniform(25.0),
+            &font,
+            &count.to_string(),
+        );
+    }
+    // drive discs
+    let mut total_score = 0.0;
+    for disc in agent.discs.iter() {
+        let index = disc.slot.saturating_sub(1).min(5) as i32;
+        let y = 45 + 173 * index;
+        if let Ok(img) = api.asset(&disc.suit_icon).await {
+            let img = resize(&img, 120, 120, FilterType::Triangle);
+            overlay(&mut base_image, &img, 1300, y as i64);
+        }
+        let level = format!("+{}", disc.level);
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            1340,
+            y + 125,
+            Scale::uniform(25.0),
+            &font,
+            &level,
+        );
+        draw_text_resized(
+            &mut base_image,
+            WHITE,
+            1440,
+            y + 5,
+            Scale::uniform(25.0),
+            &font,
+            &disc.main_stat.0.to_string_locale(lang),
+            150,
+        );
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            1600,
+            y + 5,
+            Scale::uniform(25.0),
+            &font,
+            &disc.main_stat.to_string(),
+        );
+        for (sindex, sub) in disc.sub_stats.iter().enumerate() {
+            let sy = y + 40 + 28 * sindex as i32;
+            let color = if counter.weight(sub.value.0) > 0.0 {
+                WHITE
+            } else {
+                GRAY
+            };
+            draw_text_resized(
+                &mut base_image,
+                color,
+                1440,
+                sy,
+                Scale::uniform(20.0),
+                &font,
+                &sub.value.0.to_string_locale(lang),
+                130,
+            );
+            draw_text_mut(
+                &mut base_image,
+                color,
+                1580,
+                sy,
+                Scale::uniform(20.0),
+                &font,
+                &sub.value.to_string(),
+            );
+            for roll in 0..sub.rolls.min(6) {
+                draw_filled_circle_mut(
+                    &mut base_image,
+                    (1660 + 12 * roll as i32, sy + 10),
+                    4,
+                    Rgba([accent[0], accent[1], accent[2], 255]),
+                );
+            }
+        }
+        let disc_score = get_score(disc, counter);
+        total_score += disc_score;
+        let img = get_score_image(disc_score, &DISC_SCORE)?;
+        let img = resize(&img, 80, 80, FilterType::Triangle);
+        overlay(&mut base_image, &img, 1780, (y + 30) as i64);
+        let score = format!("{:.1}", disc_score);
+        draw_text_mut(
+            &mut base_image,
+            WHITE,
+            1790,
+            y + 105,
+            Scale::uniform(30.0),
+            &font,
+            &score,
+        );
+    }
+    // all
+    let total = t!(lang, "main:zzz.total");
+    draw_text_mut(
+        &mut base_image,
+        WHITE,
+        700,
+        750,
+        Scale::uniform(50.0),
+        &font,
+        &total,
+    );
+    let calc = counter.to_string_locale(lang);
+    draw_text_mut(
+        &mut base_image,
+        WHITE,
+        900,
+        770,
+        Scale::uniform(30.0),
+        &font,
+        &calc,
+    );
+    let score = format!("{:.1}", total_score);
+    draw_text_mut(
+        &mut base_image,
+        WHITE,
+        980,
+        845,
+        Scale::uniform(70.0),
+        &font,
+        &score,
+    );
+    let img = get_score_image(total_score, &TOTAL_SCORE)?;
+    let img = resize(&img, 150, 150, FilterType::Triangle);
+    overlay(&mut base_image, &img, 760, 800);
+    convert(base_image, format).await
+}
+
+pub async fn convert(image: DynamicImage, format: ImageFormat) -> Option<Vec<u8>> {
+    let format = match format {
+        ImageFormat::Png => Some(ImageOutputFormat::Png),
+        ImageFormat::Jpeg => Some(ImageOutputFormat::Jpeg(100)),
+        ImageFormat::Raw => None,
+    };
+    if let Some(format) = format {
+        let mut buf = BufWriter::new(Cursor::new(Vec::new()));
+        image.write_to(&mut buf, format).ok()?;
+        Some(buf.into_inner().ok()?.into_inner())
+    } else {
+        Some(image.into_bytes())
+    }
+}
+
+fn parse_color(s: &str) -> Option<[u8; 3]> {
+    let s = s.trim_start_matches('#');
+    if s.len() < 6 {
+        return None;
+    }
+    Some([
+        u8::from_str_radix(s.get(0..2)?, 16).ok()?,
+        u8::from_str_radix(s.get(2..4)?, 16).ok()?,
+        u8::from_str_radix(s.get(4..6)?, 16).ok()?,
+    ])
+}
+
+fn background(accent: [u8; 3]) -> DynamicImage {
+    let img = RgbaImage::from_fn(1920, 1080, |x, y| {
+        let t = (x as f64 / 1920.0 + y as f64 / 1080.0) / 2.0;
+        let mix = |c: u8| (20.0 + (c as f64 * 0.45 - 20.0) * (1.0 - t)).max(0.0) as u8;
+        Rgba([mix(accent[0]), mix(accent[1]), mix(accent[2]), 255])
+    });
+    DynamicImage::ImageRgba8(img)
+}
+
+fn sum_stats(discs: &[DriveDisc]) -> Vec<StatValue> {
+    let mut stats: Vec<StatValue> = Vec::new();
+    for disc in discs {
+        let values =
+            std::iter::once(&disc.main_stat).chain(disc.sub_stats.iter().map(|s| &s.value));
+        for value in values {
+            match stats.iter_mut().find(|s| s.0 == value.0) {
+                Some(s) => s.1 += value.1,
+                None => stats.push(*value),
+            }
+        }
+    }
+    stats.retain(|s| !matches!(s.0, Property::Unknown(_)));
+    stats.truncate(11);
+    stats
+}
+
+fn count_suits(discs: &[DriveDisc]) -> Vec<(String, u8)> {
+    let mut order = Vec::new();
+    let mut counts: HashMap<u32, (String, u8)> = HashMap::new();
+    for disc in discs {
+        let entry = counts.entry(disc.suit_id).or_insert_with(|| {
+            order.push(disc.suit_id);
+            (disc.suit_name.clone(), 0)
+        });
+        entry.1 += 1;
+    }
+    order
+        .iter()
+        .filter_map(|id| counts.remove(id))
+        .filter(|(_, count)| *count >= 2)
+        .collect()
+}
+
+pub fn get_score(disc: &DriveDisc, counter: &ScoreCounter) -> f64 {
+    disc.sub_stats
+        .iter()
+        .map(|sub| sub.value.1 * counter.weight(sub.value.0))
+        .sum()
+}
+
+#[derive(PartialEq)]
+pub enum ScoreCounter {
+    Attack,
+    Hp,
+    Defense,
+    Anomaly,
+}
+
+impl ScoreCounter {
+    /// Weight applied to a drive disc substat when computing its score.
+    pub fn weight(&self, property: Property) -> f64 {
+        match (self, property) {
+            (Self::Anomaly, Property::AttackPercent) => 1.0,
+            (Self::Anomaly, Property::AnomalyProficiency) => 1.0 / 3.0,
+            (Self::Anomaly, _) => 0.0,
+            (_, Property::CritRate) => 2.0,
+            (_, Property::CritDmg) => 1.0,
+            (Self::Attack, Property::AttackPercent) => 1.0,
+            (Self::Hp, Property::HpPercent) => 1.0,
+            (Self::Defense, Property::DefensePercent) => 1.0,
+            _ => 0.0,
+        }
+    }
+    pub fn default_for(agent: &Agent) -> Self {
+        match agent.profession.as_str() {
+            "Anomaly" => Self::Anomaly,
+            "Defense" => Self::Defense,
+            _ => Self::Attack,
+        }
+    }
+    pub fn to_string_locale(&self, lang: &str) -> String {
+        match lang {
+            "ja-JP" | "ja" => self.ja(),
+            _ => self.en(),
+        }
+        .to_string()
+    }
+    pub fn ja(&self) -> &str {
+        match self {
+            ScoreCounter::Attack => "攻撃型",
+            ScoreCounter::Hp => "HP型",
+            ScoreCounter::Defense => "防御型",
+            ScoreCounter::Anomaly => "異常型",
+        }
+    }
+    pub fn en(&self) -> &str {
+        match self {
+            ScoreCounter::Attack => "Attack",
+            ScoreCounter::Hp => "Hp",
+            ScoreCounter::Defense => "Defense",
+            ScoreCounter::Anomaly => "Anomaly",
+        }
+    }
+}
+
+impl FromStr for ScoreCounter {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attack" => Ok(ScoreCounter::Attack),
+            "hp" => Ok(ScoreCounter::Hp),
+            "defense" => Ok(ScoreCounter::Defense),
+            "anomaly" => Ok(ScoreCounter::Anomaly),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for ScoreCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            ScoreCounter::Attack => "attack",
+            ScoreCounter::Hp => "hp",
+            ScoreCounter::Defense => "defense",
+            ScoreCounter::Anomaly => "anomaly",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text_resized(
+    canvas: &mut DynamicImage,
+    color: Rgba<u8>,
+    x: i32,
+    y: i32,
+    scale: Scale,
+    font: &Font,
+    text: &str,
+    max_width: u32,
+) {
+    let width = font
+        .layout(text, scale, rusttype::Point { x: 0.0, y: 0.0 })
+        .filter_map(|g| g.pixel_bounding_box())
+        .fold(0, |acc, g| acc + g.width());
+    if width > max_width as i32 {
+        let scale = Scale::uniform(scale.x * (max_width as f32 / width as f32));
+        draw_text_mut(canvas, color, x, y, scale, font, text);
+        return;
+    }
+    draw_text_mut(canvas, color, x, y, scale, font, text)
+}
+
+struct Score {
+    a: f64,
+    s: f64,
+    ss: f64,
+}
+
+const TOTAL_SCORE: Score = Score {
+    a: 120.0,
+    s: 160.0,
+    ss: 190.0,
+};
+
+const DISC_SCORE: Score = Score {
+    a: 20.0,
+    s: 28.0,
+    ss: 35.0,
+};
+
+fn get_score_image(score: f64, selector: &Score) -> Option<DynamicImage> {
+    let score = if score > selector.ss {
+        "SS"
+    } else if score > selector.s {
+        "S"
+    } else if score > selector.a {
+        "A"
+    } else {
+        "B"
+    };
+    get_grade_image(score)
+}
@@ -0,0 +1,3 @@
+pub use zzz_api;
+pub mod format;
+pub mod gen;
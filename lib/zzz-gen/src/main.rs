@@ -0,0 +1,19 @@
+use tokio::fs::write;
+use zzz_api::api::Api;
+use zzz_gen::{
+    format::ImageFormat,
+    gen::{generate, ScoreCounter},
+};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let api = Api::new();
+    let lang = "ja".to_string();
+    let (user, _d) = api.simple(1300000000, lang.to_string()).await.unwrap();
+    for (i, agent) in user.agents.iter().enumerate() {
+        let counter = ScoreCounter::default_for(agent);
+        if let Some(img) = generate(&api, agent, ImageFormat::Png, &lang, &counter).await {
+            write(format!("test{}.png", i), img).await.unwrap();
+        }
+    }
+}